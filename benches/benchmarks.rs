@@ -1,6 +1,6 @@
+use box_plot_chart::*;
 use core::fmt::Arguments;
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use box_plot_chart::*;
 
 fn benchmark_1(arg: &str) {
     struct TestLogger;
@@ -12,9 +12,9 @@ fn benchmark_1(arg: &str) {
     }
 
     impl BoxPlotChartLog for TestLogger {
-        fn output(self: &Self, _args: Arguments) {}
-        fn warning(self: &Self, _args: Arguments) {}
-        fn error(self: &Self, _args: Arguments) {}
+        fn output(&self, _args: Arguments) {}
+        fn warning(&self, _args: Arguments) {}
+        fn error(&self, _args: Arguments) {}
     }
 
     let logger = TestLogger::new();
@@ -0,0 +1,68 @@
+//! Build a chart in code and render it to an SVG file, or to standard output when no file
+//! is given:
+//!
+//! ```text
+//! cargo run --example render_chart -- latency.svg
+//! ```
+use box_plot_chart::*;
+use core::fmt::Arguments;
+
+struct ExampleLogger;
+
+impl BoxPlotChartLog for ExampleLogger {
+    fn output(&self, args: Arguments) {
+        println!("{}", args);
+    }
+    fn warning(&self, args: Arguments) {
+        eprintln!("warning: {}", args);
+    }
+    fn error(&self, args: Arguments) {
+        eprintln!("error: {}", args);
+    }
+}
+
+fn render_chart() -> Result<String, Box<dyn std::error::Error>> {
+    let logger = ExampleLogger;
+    let tool = BoxPlotChartTool::new(&logger);
+    let cd = ChartData::new("Request Latency", "ms")
+        .with_item(ItemData::new(
+            "us-east",
+            &[12.0, 15.0, 14.0, 18.0, 22.0, 13.0, 95.0],
+        ))
+        .with_item(ItemData::new(
+            "us-west",
+            &[20.0, 24.0, 19.0, 27.0, 25.0, 23.0],
+        ))
+        .with_item(ItemData::new(
+            "eu-central",
+            &[31.0, 35.0, 29.0, 40.0, 33.0, 38.0],
+        ));
+    let opts = RenderOptions {
+        theme: Theme::Dark,
+        zero_line: true,
+        ..Default::default()
+    };
+    let svg = tool.render_to_string(&cd, &opts)?;
+
+    assert!(svg.starts_with("<svg"));
+    assert_eq!(svg.matches(r#"<g class="box-plot""#).count(), cd.data.len());
+
+    Ok(svg)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let svg = render_chart()?;
+
+    match std::env::args_os().nth(1) {
+        Some(path) => std::fs::write(path, svg)?,
+        None => println!("{}", svg),
+    }
+
+    Ok(())
+}
+
+// Run by `cargo test`, which builds this example with the test harness
+#[test]
+fn render_chart_test() {
+    assert!(render_chart().unwrap().contains(Theme::Dark.background()));
+}
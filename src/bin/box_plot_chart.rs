@@ -1,4 +1,4 @@
-use box_plot_chart::{error, BoxPlotChartLog, BoxPlotChartTool};
+use box_plot_chart::{error, error::exit_code, BoxPlotChartLog, BoxPlotChartTool};
 use core::fmt::Arguments;
 use yansi::Paint;
 
@@ -11,13 +11,13 @@ impl BoxPlotChartLogger {
 }
 
 impl BoxPlotChartLog for BoxPlotChartLogger {
-    fn output(self: &Self, args: Arguments) {
+    fn output(&self, args: Arguments) {
         println!("{}", args);
     }
-    fn warning(self: &Self, args: Arguments) {
+    fn warning(&self, args: Arguments) {
         eprintln!("{}", Paint::yellow(&format!("warning: {}", args)));
     }
-    fn error(self: &Self, args: Arguments) {
+    fn error(&self, args: Arguments) {
         eprintln!("{}", Paint::red(&format!("error: {}", args)));
     }
 }
@@ -26,7 +26,12 @@ fn main() {
     let logger = BoxPlotChartLogger::new();
 
     if let Err(error) = BoxPlotChartTool::new(&logger).run(std::env::args_os()) {
-        error!(logger, "{}", error);
-        std::process::exit(1);
+        // Command line errors come formatted by clap, with usage and their own prefix
+        match error.source().and_then(|e| e.downcast_ref::<clap::Error>()) {
+            Some(err) => eprint!("{}", err),
+            None => error!(logger, "{}", error),
+        }
+
+        std::process::exit(exit_code(error.as_ref()));
     }
 }
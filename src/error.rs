@@ -0,0 +1,109 @@
+use std::{error::Error, fmt};
+
+/// The class of failure, which determines the process exit code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Invalid command line arguments, or option values and files that cannot be used
+    /// (exit code 2)
+    Usage,
+    /// The input file could not be opened (exit code 3)
+    InputNotFound,
+    /// The input could not be read or parsed (exit code 4)
+    Parse,
+    /// The chart data could not be processed or rendered (exit code 5)
+    Render,
+    /// An output file could not be created or written (exit code 6)
+    Write,
+    /// Any other file system failure, such as being unable to watch the input (exit code 7)
+    Io,
+}
+
+impl ErrorKind {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorKind::Usage => 2,
+            ErrorKind::InputNotFound => 3,
+            ErrorKind::Parse => 4,
+            ErrorKind::Render => 5,
+            ErrorKind::Write => 6,
+            ErrorKind::Io => 7,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BoxPlotChartError {
+    kind: ErrorKind,
+    source: Box<dyn Error>,
+}
+
+impl BoxPlotChartError {
+    pub fn new(kind: ErrorKind, source: Box<dyn Error>) -> BoxPlotChartError {
+        BoxPlotChartError { kind, source }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// A closure for `map_err` that classifies an error as `kind`
+    pub fn of(kind: ErrorKind) -> impl Fn(Box<dyn Error>) -> Box<dyn Error> {
+        move |source| Box::new(BoxPlotChartError::new(kind, source))
+    }
+}
+
+impl fmt::Display for BoxPlotChartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Error for BoxPlotChartError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// A problem with chart data found by `ChartData::validate`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// The chart has no title
+    EmptyTitle,
+    /// The chart has no items
+    NoItems,
+    /// An item has fewer values than the 3 needed for quartiles
+    TooFewValues { key: String, count: usize },
+    /// An item has a NaN or infinite value at `index`
+    NonFiniteValue { key: String, index: usize },
+    /// More than one item has the same key
+    DuplicateKey(String),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::EmptyTitle => write!(f, "Chart title is empty"),
+            ValidationError::NoItems => write!(f, "Chart has no items"),
+            ValidationError::TooFewValues { key, count } => write!(
+                f,
+                "Item '{}' has {} values but needs at least 3",
+                key, count
+            ),
+            ValidationError::NonFiniteValue { key, index } => {
+                write!(f, "Item '{}' value {} is not a finite number", key, index)
+            }
+            ValidationError::DuplicateKey(key) => write!(f, "Item key '{}' is duplicated", key),
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+/// The process exit code for an error returned by `BoxPlotChartTool::run`; errors that
+/// have not been classified exit with 1
+pub fn exit_code(error: &(dyn Error + 'static)) -> i32 {
+    match error.downcast_ref::<BoxPlotChartError>() {
+        Some(err) => err.kind().exit_code(),
+        None => 1,
+    }
+}
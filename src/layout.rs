@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+/// Positions and sizes of the elements of a rendered chart, in unscaled SVG coordinates
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChartLayout {
+    pub width: f64,
+    pub height: f64,
+    /// The area bounded by the axes
+    pub plot: Rect,
+    /// Y axis ticks from the lowest value to the highest
+    pub ticks: Vec<Tick>,
+    pub items: Vec<ItemLayout>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Tick {
+    pub value: f64,
+    pub label: String,
+    pub y: f64,
+}
+
+/// The layout of a single box plot
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ItemLayout {
+    pub key: String,
+    /// The horizontal center of the box plot
+    pub x: f64,
+    /// The interquartile box, from the upper quartile at the top to the lower quartile
+    #[serde(rename = "box")]
+    pub box_rect: Rect,
+    pub median_y: f64,
+    pub whiskers: Whiskers,
+    pub outliers: Vec<Outlier>,
+    /// Mean plus and minus one standard deviation, when error bars are drawn
+    pub error_bar: Option<ErrorBar>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Whiskers {
+    pub top: f64,
+    pub bottom: f64,
+    pub width: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ErrorBar {
+    pub x: f64,
+    pub mean_y: f64,
+    pub top: f64,
+    pub bottom: f64,
+    pub width: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Outlier {
+    pub value: f64,
+    /// The number of coincident outliers drawn by this marker
+    pub count: usize,
+    pub cx: f64,
+    pub cy: f64,
+    pub r: f64,
+}
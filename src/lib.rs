@@ -1,379 +1,7678 @@
+pub mod error;
+pub mod layout;
 mod log_macros;
+pub mod number_format;
+#[cfg(feature = "pdf")]
+mod pdf;
 pub mod quartile;
+pub mod summary;
+pub mod template;
+#[cfg(test)]
+#[path = "../tests/common/mod.rs"]
+mod test_common;
+#[cfg(feature = "watch")]
+pub mod watch;
+pub mod whitespace;
 
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser, ValueSource};
 use core::fmt::Arguments;
 use easy_error::{self, ResultExt};
-use quartile::Quartile;
-use serde::Deserialize;
+use error::{BoxPlotChartError, ErrorKind, ValidationError};
+use layout::{ChartLayout, ErrorBar, ItemLayout, Outlier, Rect, Tick, Whiskers};
+use number_format::NumberFormat;
+use quartile::{Quartile, QuartileMethod, VarianceKind, WhiskerMode};
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
 use std::{
+    collections::{BTreeMap, HashMap, HashSet},
     error::Error,
+    ffi::OsString,
+    fmt,
     fs::File,
     io::{self, Read, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
-use svg::{node::element::*, node::*, Document};
+use summary::{CategorySummary, ChartSummary, GlobalSummary};
+use svg::{node::element::*, node::*};
+
+pub use svg::Document;
 
 pub trait BoxPlotChartLog {
-    fn output(self: &Self, args: Arguments);
-    fn warning(self: &Self, args: Arguments);
-    fn error(self: &Self, args: Arguments);
+    fn output(&self, args: Arguments);
+    fn warning(&self, args: Arguments);
+    fn error(&self, args: Arguments);
 }
 
 pub struct BoxPlotChartTool<'a> {
     log: &'a dyn BoxPlotChartLog,
 }
 
+/// A backend that turns a laid out chart into some output.  `BoxPlotChartTool` renders SVG
+/// documents and `TextRenderer` summary tables.
+pub trait ChartRenderer {
+    type Output;
+
+    fn render(&self, rd: &RenderData) -> Result<Self::Output, Box<dyn Error>>;
+}
+
+impl ChartRenderer for BoxPlotChartTool<'_> {
+    type Output = Document;
+
+    fn render(&self, rd: &RenderData) -> Result<Document, Box<dyn Error>> {
+        self.render_chart(rd)
+    }
+}
+
+/// Renders the table of summary statistics written by `--output-format text`
+pub struct TextRenderer;
+
+impl ChartRenderer for TextRenderer {
+    type Output = String;
+
+    fn render(&self, rd: &RenderData) -> Result<String, Box<dyn Error>> {
+        Ok(summary_table(rd))
+    }
+}
+
 #[derive(Parser)]
 #[clap(version, about, long_about = None)]
 struct Cli {
-    /// The JSON5 input file
+    /// The JSON5 or CSV input file
     #[clap(value_name = "INPUT_FILE")]
     input_file: Option<PathBuf>,
 
     /// The SVG output file
     #[clap(value_name = "OUTPUT_FILE")]
     output_file: Option<PathBuf>,
+
+    /// A JSON5 file of default options, keyed by their long names, that those given on the
+    /// command line override; flags are set with true
+    #[clap(long = "config", value_name = "CONFIG_FILE")]
+    config: Option<PathBuf>,
+
+    /// The input file, leaving a lone OUTPUT_FILE to be given by position
+    #[clap(long = "input", value_name = "FILE")]
+    input: Option<PathBuf>,
+
+    /// The output file, so that standard input can be read without naming an input file
+    #[clap(long = "output", value_name = "FILE", conflicts_with = "output-file")]
+    output: Option<PathBuf>,
+
+    /// Write styles to a separate CSS file referenced from the SVG
+    #[clap(long = "external-css")]
+    external_css: bool,
+
+    /// Draw a horizontal baseline at zero when it falls within the Y axis range
+    #[clap(long = "zero-line")]
+    zero_line: bool,
+
+    /// Put the highest values at the bottom of the Y axis, for metrics where lower is better
+    #[clap(long = "invert-y")]
+    invert_y: bool,
+
+    /// Extend the Y axis range so that it always includes zero
+    #[clap(long = "include-zero")]
+    include_zero: bool,
+
+    /// Author recorded in the SVG metadata
+    #[clap(long = "author", value_name = "AUTHOR")]
+    author: Option<String>,
+
+    /// Data source recorded in the SVG metadata
+    #[clap(long = "source", value_name = "SOURCE")]
+    source: Option<String>,
+
+    /// An SVG template file into which the chart is inserted
+    #[clap(long = "template-svg", value_name = "TEMPLATE_FILE")]
+    template_svg: Option<PathBuf>,
+
+    /// The id of the template element that receives the chart
+    #[clap(long = "template-id", value_name = "ID", default_value = "chart")]
+    template_id: String,
+
+    /// How the lower and upper quartiles are calculated
+    #[clap(
+        long = "quartile-method",
+        value_enum,
+        value_name = "METHOD",
+        default_value = "exclusive"
+    )]
+    quartile_method: QuartileMethod,
+
+    /// Draw whiskers to the furthest values within the given lower and upper percentiles
+    /// instead of 1.5 IQR
+    #[clap(long = "whisker-percentiles", value_name = "LO,HI", parse(try_from_str = parse_pair))]
+    whisker_percentiles: Option<(f64, f64)>,
+
+    /// Multiply every value by this factor, given as a number or a ratio such as 1/1024
+    #[clap(long = "value-scale", value_name = "FACTOR", parse(try_from_str = parse_ratio))]
+    value_scale: Option<f64>,
+
+    /// Add this amount to every value after scaling it
+    #[clap(
+        long = "value-offset",
+        value_name = "OFFSET",
+        allow_hyphen_values = true
+    )]
+    value_offset: Option<f64>,
+
+    /// Units of the values after scaling them, replacing the chart units
+    #[clap(long = "value-units", value_name = "UNITS")]
+    value_units: Option<String>,
+
+    /// Rescale values to percentages of a range to compare shapes rather than magnitudes
+    #[clap(long = "normalize", value_enum, value_name = "MODE")]
+    normalize: Option<Normalization>,
+
+    /// Shade the acceptable range between the given values behind the box plots
+    #[clap(long = "target-band", value_name = "LO,HI", parse(try_from_str = parse_pair))]
+    target_band: Option<(f64, f64)>,
+
+    /// Radius of the outlier markers
+    #[clap(long = "outlier-radius", value_name = "RADIUS")]
+    outlier_radius: Option<f64>,
+
+    /// Stroke color of the outlier markers, regardless of the box plot color
+    #[clap(long = "outlier-color", value_name = "COLOR")]
+    outlier_color: Option<String>,
+
+    /// Shrink outlier markers where they cluster densely
+    #[clap(long = "density-radius")]
+    density_radius: bool,
+
+    /// A JSON5 file mapping category keys to colors
+    #[clap(long = "color-map", value_name = "COLOR_MAP_FILE")]
+    color_map: Option<PathBuf>,
+
+    /// Color each category from a palette by a hash of its key, so that a key keeps its
+    /// color across charts
+    #[clap(long = "stable-colors")]
+    stable_colors: bool,
+
+    /// Multiply the output size of the SVG, scaling all content uniformly
+    #[clap(long = "scale", value_name = "FACTOR", parse(try_from_str = parse_positive))]
+    scale: Option<f64>,
+
+    /// Additional JSON5 input files rendered alongside the first as a grid of charts
+    #[clap(
+        long = "grid-input",
+        value_name = "INPUT_FILE",
+        multiple_occurrences = true
+    )]
+    grid_inputs: Vec<PathBuf>,
+
+    /// The number of columns in a grid of charts
+    #[clap(long = "grid-columns", value_name = "COLUMNS", default_value = "2")]
+    grid_columns: usize,
+
+    /// Use the same Y axis range for every chart in a grid
+    #[clap(long = "shared-y")]
+    shared_y: bool,
+
+    /// Stack the grid charts vertically, drawing their shared X axis labels once at the bottom
+    #[clap(long = "stack")]
+    stack: bool,
+
+    /// Render only the title and a legend of the category colors, without the box plots
+    #[clap(long = "title-only", conflicts_with = "grid-inputs")]
+    title_only: bool,
+
+    /// How each category is drawn
+    #[clap(
+        long = "style",
+        value_enum,
+        value_name = "STYLE",
+        default_value = "box"
+    )]
+    style: PlotStyle,
+
+    /// Dash the whiskers with a pattern of dash and gap lengths, e.g. `4,2`
+    #[clap(long = "whisker-dash", value_name = "PATTERN", parse(try_from_str = parse_dash))]
+    whisker_dash: Option<String>,
+
+    /// Space between the whiskers and the box
+    #[clap(long = "whisker-gap", value_name = "GAP")]
+    whisker_gap: Option<f64>,
+
+    /// How the median of each box is marked
+    #[clap(
+        long = "median-style",
+        value_enum,
+        value_name = "STYLE",
+        default_value = "line"
+    )]
+    median_style: MedianStyle,
+
+    /// How a category whose values are all the same is drawn
+    #[clap(
+        long = "zero-spread",
+        value_enum,
+        value_name = "STYLE",
+        default_value = "line"
+    )]
+    zero_spread: ZeroSpread,
+
+    /// Dash the median line with a pattern of dash and gap lengths, e.g. `2,2`
+    #[clap(long = "median-dash", value_name = "PATTERN", parse(try_from_str = parse_dash))]
+    median_dash: Option<String>,
+
+    /// How the corners of the box plot lines are joined
+    #[clap(long = "linejoin", value_enum, value_name = "JOIN")]
+    linejoin: Option<LineJoin>,
+
+    /// How the ends of the box plot lines are capped
+    #[clap(long = "linecap", value_enum, value_name = "CAP")]
+    linecap: Option<LineCap>,
+
+    /// Stroke color of the axis lines, defaulting to that of the theme
+    #[clap(long = "axis-color", value_name = "COLOR")]
+    axis_color: Option<String>,
+
+    /// Fill color of the axis labels, defaulting to that of the theme
+    #[clap(long = "label-color", value_name = "COLOR")]
+    label_color: Option<String>,
+
+    /// Draw the mean plus and minus one standard deviation beside each box plot
+    #[clap(long = "error-bars")]
+    error_bars: bool,
+
+    /// Draw faint guides across each box at its quartiles and median
+    #[clap(long = "box-guides")]
+    box_guides: bool,
+
+    /// Add a tooltip to each box plot giving its median and quartiles
+    #[clap(long = "tooltips")]
+    tooltips: bool,
+
+    /// Shade the background of every other category column
+    #[clap(long = "zebra")]
+    zebra: bool,
+
+    /// Draw faint lines joining the highest and lowest points of adjacent box plots
+    #[clap(long = "envelope")]
+    envelope: bool,
+
+    /// Rendering hint for the axes and zero line, such as `crisp-edges`
+    #[clap(long = "axis-shape-rendering", value_enum, value_name = "HINT")]
+    axis_shape_rendering: Option<ShapeRendering>,
+
+    /// Rendering hint for the box plots, such as `geometric-precision`
+    #[clap(long = "box-shape-rendering", value_enum, value_name = "HINT")]
+    box_shape_rendering: Option<ShapeRendering>,
+
+    /// Draw coincident outliers as one marker whose opacity increases with their count
+    #[clap(long = "merge-outliers")]
+    merge_outliers: bool,
+
+    /// Print the value of each outlier next to its marker
+    #[clap(long = "label-outliers")]
+    label_outliers: bool,
+
+    /// Print the interquartile range of each box beside it
+    #[clap(long = "label-iqr")]
+    label_iqr: bool,
+
+    /// Add the values of each box to its group as a JSON `data-values` attribute, for
+    /// scripts on the page.  This can make charts of large data sets much bigger.
+    #[clap(long = "embed-data")]
+    embed_data: bool,
+
+    /// Wrap a title wider than the chart onto several lines, deepening the top gutter to fit
+    #[clap(long = "wrap-title")]
+    wrap_title: bool,
+
+    /// Space between the last line of the title and the top of the plot, setting the depth
+    /// of the top gutter
+    #[clap(long = "title-spacing", value_name = "SPACING")]
+    title_spacing: Option<f64>,
+
+    /// Omit the outlier markers, leaving the whiskers at the fences
+    #[clap(long = "no-outliers")]
+    no_outliers: bool,
+
+    /// Fit the automatic Y axis range to the whiskers rather than the outliers
+    #[clap(long = "autorange-exclude-outliers", requires = "no-outliers")]
+    autorange_exclude_outliers: bool,
+
+    /// Combine the values of categories with the same key into a single box
+    #[clap(long = "merge-duplicates")]
+    merge_duplicates: bool,
+
+    /// Set the height of the plot area from its width to keep this aspect ratio
+    #[clap(long = "plot-aspect", value_name = "W:H", parse(try_from_str = parse_aspect))]
+    plot_aspect: Option<(f64, f64)>,
+
+    /// Maximum width of the SVG; box plots are narrowed to fit unless `--downsample` is given
+    #[clap(long = "max-width", value_name = "WIDTH")]
+    max_width: Option<f64>,
+
+    /// Minimum width of the SVG; the plot is centered within it by widening the side gutters
+    #[clap(long = "min-width", value_name = "WIDTH")]
+    min_width: Option<f64>,
+
+    /// Drop categories, rather than narrowing box plots, to fit within `--max-width`
+    #[clap(long = "downsample", requires = "max-width")]
+    downsample: bool,
+
+    /// Create the directories of output files if they do not exist
+    #[clap(long = "mkdir")]
+    mkdir: bool,
+
+    /// Colors of the chart
+    #[clap(
+        long = "theme",
+        value_enum,
+        value_name = "THEME",
+        default_value = "light"
+    )]
+    theme: Theme,
+
+    /// Write light and dark themed charts to OUTPUT_FILE with `-light` and `-dark` added
+    #[clap(long = "dual-theme", conflicts_with = "theme")]
+    dual_theme: bool,
+
+    /// Split the categories into charts of at most N each, written to OUTPUT_FILE with the
+    /// page number replacing `{page}` or, failing that, added as `-1`, `-2` and so on
+    #[clap(long = "per-page", value_name = "N", conflicts_with = "grid-inputs")]
+    per_page: Option<usize>,
+
+    /// Background color of the chart, defaulting to that of the theme
+    #[clap(long = "background", value_name = "COLOR")]
+    background: Option<String>,
+
+    /// Draw the background as a rectangle rather than a style, for renderers that ignore it
+    #[clap(long = "bg-rect")]
+    bg_rect: bool,
+
+    /// Omit the width and height of the SVG so that it scales to fit its container
+    #[clap(long = "responsive")]
+    responsive: bool,
+
+    /// Class of the root SVG element, for targeting the chart from a page's CSS
+    #[clap(long = "root-class", value_name = "CLASS")]
+    root_class: Option<String>,
+
+    /// How the chart fits its container, such as "xMidYMid meet" or "none"
+    #[clap(long = "preserve-aspect-ratio", value_name = "VALUE")]
+    preserve_aspect_ratio: Option<String>,
+
+    /// Remove the whitespace between elements of the SVG
+    #[clap(long = "minify", conflicts_with = "pretty")]
+    minify: bool,
+
+    /// Indent the elements of the SVG, one per line
+    #[clap(long = "pretty")]
+    pretty: bool,
+
+    /// Write the position and size of every chart element to a JSON file
+    #[clap(
+        long = "layout-json",
+        value_name = "LAYOUT_FILE",
+        conflicts_with = "grid-inputs"
+    )]
+    layout_json: Option<PathBuf>,
+
+    /// Write the statistics of all the values and of each category to a JSON file
+    #[clap(
+        long = "summary-json",
+        value_name = "SUMMARY_FILE",
+        conflicts_with = "grid-inputs"
+    )]
+    summary_json: Option<PathBuf>,
+
+    /// Add an "All" box plot combining the values of every category shown
+    #[clap(long = "with-total")]
+    with_total: bool,
+
+    /// Comma separated category keys in the order to draw them; others follow as given
+    #[clap(long = "order", value_name = "KEYS", use_value_delimiter = true)]
+    order: Vec<String>,
+
+    /// Comma separated values at which to label the Y axis instead of uniform intervals
+    #[clap(long = "y-ticks-at", value_name = "VALUES", use_value_delimiter = true)]
+    y_ticks_at: Vec<f64>,
+
+    /// Most Y axis ticks to draw, evenly thinning any more given by `--y-ticks-at`
+    #[clap(long = "max-ticks", value_name = "COUNT")]
+    max_ticks: Option<usize>,
+
+    /// Most intervals into which the Y axis is divided, rather than one per 20 or so pixels
+    #[clap(long = "y-intervals", value_name = "COUNT")]
+    y_intervals: Option<usize>,
+
+    /// Decimal places shown in Y axis tick labels
+    #[clap(long = "y-tick-precision", value_name = "PLACES")]
+    y_tick_precision: Option<usize>,
+
+    /// Decimal places shown in value labels and tooltips
+    #[clap(long = "value-precision", value_name = "PLACES")]
+    value_precision: Option<usize>,
+
+    /// Significant figures shown in all numeric labels, instead of decimal places
+    #[clap(long = "sig-figs", value_name = "FIGURES")]
+    sig_figs: Option<usize>,
+
+    /// Decimal places beyond which numeric labels use scientific notation
+    #[clap(long = "max-decimals", value_name = "PLACES")]
+    max_decimals: Option<usize>,
+
+    /// Locale used to format numeric labels, e.g. `de` or `en-US`
+    #[clap(long = "locale", value_name = "LOCALE")]
+    locale: Option<String>,
+
+    /// The format of the input data; detected from the file extension or content if omitted
+    #[clap(long = "format", value_enum, value_name = "FORMAT")]
+    format: Option<InputFormat>,
+
+    /// The file format the chart is written in
+    #[clap(
+        long = "output-format",
+        value_enum,
+        value_name = "FORMAT",
+        default_value = "svg",
+        conflicts_with = "external-css"
+    )]
+    output_format: OutputFormat,
+
+    /// The CSV header of the column holding category keys, otherwise the first column
+    #[clap(long = "key-column", value_name = "COLUMN")]
+    key_column: Option<String>,
+
+    /// Comma separated CSV headers of the columns holding values, otherwise all other columns
+    #[clap(
+        long = "value-columns",
+        value_name = "COLUMNS",
+        use_value_delimiter = true
+    )]
+    value_columns: Vec<String>,
+
+    /// Re-render the chart whenever the input file changes
+    #[cfg(feature = "watch")]
+    #[clap(long = "watch")]
+    watch: bool,
 }
 
-impl Cli {
-    fn get_output(&self) -> Result<Box<dyn Write>, Box<dyn Error>> {
-        match self.output_file {
-            Some(ref path) => File::create(path)
-                .context(format!(
-                    "Unable to create file '{}'",
-                    path.to_string_lossy()
-                ))
-                .map(|f| Box::new(f) as Box<dyn Write>)
-                .map_err(|e| Box::new(e) as Box<dyn Error>),
-            None => Ok(Box::new(io::stdout())),
+/// The format of chart input data
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// A JSON5 object with `title`, `units` and `data`
+    Json5,
+    /// One row per category, the key followed by its values
+    Csv,
+}
+
+impl InputFormat {
+    /// The format implied by the extension of `path`, if any
+    pub fn from_path(path: &Path) -> Option<InputFormat> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(InputFormat::Csv),
+            "json" | "json5" => Some(InputFormat::Json5),
+            _ => None,
         }
     }
 
-    fn get_input(&self) -> Result<Box<dyn Read>, Box<dyn Error>> {
-        match self.input_file {
-            Some(ref path) => File::open(path)
-                .context(format!("Unable to open file '{}'", path.to_string_lossy()))
-                .map(|f| Box::new(f) as Box<dyn Read>)
-                .map_err(|e| Box::new(e) as Box<dyn Error>),
-            None => Ok(Box::new(io::stdin())),
+    /// Guess the format from the content; JSON5 starts with an object or array
+    pub fn sniff(content: &str) -> InputFormat {
+        match content.trim_start().chars().next() {
+            Some('{') | Some('[') => InputFormat::Json5,
+            _ => InputFormat::Csv,
         }
     }
 }
 
-#[derive(Deserialize, Debug, Clone)]
-pub struct ChartData {
-    pub title: String,
-    pub units: String,
-    pub data: Vec<ItemData>,
+/// How each category of a chart is drawn
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlotStyle {
+    /// A box spanning the interquartile range with whiskers
+    #[default]
+    Box,
+    /// A thin line between the whisker ends with a dot at the median
+    Lollipop,
+    /// Only a dot at the median, without whiskers or outliers
+    Strip,
 }
 
-#[derive(Deserialize, Debug, Clone)]
-pub struct ItemData {
-    pub key: String,
-    pub values: Vec<f64>,
+/// How the median of a box is marked
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MedianStyle {
+    /// A line across the box
+    #[default]
+    Line,
+    /// A dot in the middle of the box
+    Dot,
+    /// A line across a box notched to the approximate 95% confidence interval of the median
+    Notch,
+    /// A dot in the middle of a notched box
+    DotNotch,
 }
 
-#[derive(Debug)]
-struct Gutter {
-    left: f64,
-    top: f64,
-    right: f64,
-    bottom: f64,
+impl MedianStyle {
+    fn is_notched(&self) -> bool {
+        matches!(self, MedianStyle::Notch | MedianStyle::DotNotch)
+    }
+
+    fn is_dot(&self) -> bool {
+        matches!(self, MedianStyle::Dot | MedianStyle::DotNotch)
+    }
 }
 
-#[derive(Debug)]
-struct RenderData {
-    title: String,
-    units: String,
-    y_axis_height: f64,
-    y_axis_range: (f64, f64),
-    y_axis_interval: f64,
-    y_axis_decimal_places: usize,
-    gutter: Gutter,
-    box_plot_width: f64,
-    outlier_radius: f64,
-    styles: Vec<String>,
-    quartile_tuples: Vec<(String, Quartile)>,
+/// How a box with no spread, because all of its values are the same, is drawn
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZeroSpread {
+    /// A bold median line alone
+    #[default]
+    Line,
+    /// A bold median line in a thin box
+    Box,
 }
 
-impl<'a> BoxPlotChartTool<'a> {
-    pub fn new(log: &'a dyn BoxPlotChartLog) -> BoxPlotChartTool {
-        BoxPlotChartTool { log }
-    }
+/// How values are rescaled to percentages before computing quartiles
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// From 0 at the smallest to 100 at the largest value of each category
+    PerCategory,
+}
 
-    pub fn run(
-        self: &mut Self,
-        args: impl IntoIterator<Item = std::ffi::OsString>,
-    ) -> Result<(), Box<dyn Error>> {
-        let cli = match Cli::try_parse_from(args) {
-            Ok(m) => m,
-            Err(err) => {
-                output!(self.log, "{}", err.to_string());
-                return Ok(());
-            }
-        };
+/// The colors of a chart
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Theme {
+    /// Black on white
+    #[default]
+    Light,
+    /// Light gray on near black
+    Dark,
+}
 
-        let chart_data = Self::read_chart_file(cli.get_input()?)?;
-        let render_data = self.process_chart_data(&chart_data)?;
-        let document = self.render_chart(&render_data)?;
+impl Theme {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
 
-        Self::write_svg_file(cli.get_output()?, &document)?;
+    /// The color of the chart background
+    pub fn background(&self) -> &'static str {
+        match self {
+            Theme::Light => "white",
+            Theme::Dark => "rgb(24,24,24)",
+        }
+    }
 
-        Ok(())
+    /// The color of the lines and text drawn over the background
+    pub fn foreground(&self) -> &'static str {
+        match self {
+            Theme::Light => "rgb(0,0,0)",
+            Theme::Dark => "rgb(230,230,230)",
+        }
     }
+}
 
-    fn read_chart_file(mut reader: Box<dyn Read>) -> Result<ChartData, Box<dyn Error>> {
-        let mut content = String::new();
+/// A `shape-rendering` hint trading the precision of edges against their sharpness
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeRendering {
+    /// Let the renderer choose
+    Auto,
+    /// Favor drawing speed over quality
+    OptimizeSpeed,
+    /// Align edges to pixels, keeping thin horizontal and vertical lines sharp
+    CrispEdges,
+    /// Anti-alias edges, drawing their geometry exactly
+    GeometricPrecision,
+}
 
-        reader.read_to_string(&mut content)?;
+impl ShapeRendering {
+    /// The value of the SVG attribute
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShapeRendering::Auto => "auto",
+            ShapeRendering::OptimizeSpeed => "optimizeSpeed",
+            ShapeRendering::CrispEdges => "crispEdges",
+            ShapeRendering::GeometricPrecision => "geometricPrecision",
+        }
+    }
+}
 
-        let chart_data: ChartData = json5::from_str(&content)?;
+/// The shape of the corners where the lines of a box plot meet
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
 
-        Ok(chart_data)
+impl LineJoin {
+    /// The value of the `stroke-linejoin` property
+    pub fn name(&self) -> &'static str {
+        match self {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        }
     }
+}
 
-    fn write_svg_file(writer: Box<dyn Write>, document: &Document) -> Result<(), Box<dyn Error>> {
-        svg::write(writer, document)?;
+/// The shape of the open ends of the lines of a box plot
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
 
-        Ok(())
+impl LineCap {
+    /// The value of the `stroke-linecap` property
+    pub fn name(&self) -> &'static str {
+        match self {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        }
     }
+}
 
-    fn process_chart_data(self: &Self, cd: &ChartData) -> Result<RenderData, Box<dyn Error>> {
-        let mut quartile_tuples: Vec<(String, Quartile)> = vec![];
-        let mut y_axis_range: (f64, f64) = (f64::MAX, f64::MIN);
+/// The file format a chart is written in by `render_to`
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Svg,
+    /// A standalone HTML page containing the SVG
+    Html,
+    /// A `data:` URI of the SVG for inlining in HTML or Markdown
+    DataUri,
+    /// A plain text table of the five-number summary and outlier counts of each category
+    Text,
+    /// A bitmap of the SVG at its scaled size
+    #[cfg(feature = "png")]
+    Png,
+    /// A single page vector document the size of the SVG, for print
+    #[cfg(feature = "pdf")]
+    Pdf,
+}
 
-        for item_data in cd.data.iter() {
-            let quartile = Quartile::new(&item_data.values)?;
-            let min_value = quartile.min_value();
-            let max_value = quartile.max_value();
+/// CSV columns selected by header name.  When neither is given the CSV has no header row.
+#[derive(Debug, Clone, Default)]
+pub struct CsvColumns {
+    /// The column holding category keys, defaulting to the first column
+    pub key: Option<String>,
+    /// The columns holding values, defaulting to every column except the key
+    pub values: Vec<String>,
+}
 
-            if min_value < y_axis_range.0 {
-                y_axis_range.0 = min_value;
-            }
+fn parse_pair(s: &str) -> Result<(f64, f64), String> {
+    let parts: Vec<&str> = s.split(',').map(|p| p.trim()).collect();
 
-            if max_value > y_axis_range.1 {
-                y_axis_range.1 = max_value;
-            }
+    match parts[..] {
+        [a, b] => match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(a), Ok(b)) => Ok((a, b)),
+            _ => Err(format!("'{}' must be two comma separated numbers", s)),
+        },
+        _ => Err(format!("'{}' must be two comma separated numbers", s)),
+    }
+}
 
-            quartile_tuples.push((item_data.key.to_owned(), quartile));
-        }
+fn parse_ratio(s: &str) -> Result<f64, String> {
+    let parse = |n: &str| n.trim().parse::<f64>().ok();
+    let ratio = match s.split_once('/') {
+        Some((a, b)) => parse(a).zip(parse(b)).map(|(a, b)| a / b),
+        None => parse(s),
+    };
 
-        let y_axis_max_intervals = 20;
-        let y_axis_interval = (10.0_f64).powf(((y_axis_range.1 - y_axis_range.0).log10()).ceil())
-            / (y_axis_max_intervals as f64);
-        let decimal_places = y_axis_interval.log10();
-        let y_axis_decimal_places = if decimal_places < 0.0 {
-            decimal_places.abs().ceil() as usize
-        } else {
-            0
-        };
+    match ratio {
+        Some(ratio) if ratio.is_finite() => Ok(ratio),
+        _ => Err(format!(
+            "'{}' must be a number or a ratio of two numbers",
+            s
+        )),
+    }
+}
 
-        y_axis_range = (
-            f64::floor(y_axis_range.0 / y_axis_interval) * y_axis_interval,
-            f64::ceil(y_axis_range.1 / y_axis_interval) * y_axis_interval,
-        );
+fn parse_positive(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(n) if n.is_finite() && n > 0.0 => Ok(n),
+        _ => Err(format!("'{}' must be a number greater than zero", s)),
+    }
+}
 
-        let gutter = Gutter {
-            top: 40.0,
-            bottom: 80.0,
-            left: 80.0,
-            right: 80.0,
-        };
-        let y_axis_height = 400.0;
-        let box_plot_width = 60.0;
+fn parse_aspect(s: &str) -> Result<(f64, f64), String> {
+    match s
+        .split_once(':')
+        .map(|(w, h)| (w.trim().parse(), h.trim().parse()))
+    {
+        Some((Ok(w), Ok(h))) if w > 0.0 && h > 0.0 && f64::is_finite(w / h) => Ok((w, h)),
+        _ => Err(format!("'{}' must be a positive ratio such as 16:9", s)),
+    }
+}
 
-        Ok(RenderData {
-            title: cd.title.to_owned(),
-            units: cd.units.to_owned(),
-            y_axis_height,
-            y_axis_range,
-            y_axis_interval,
-            y_axis_decimal_places,
-            gutter,
-            box_plot_width,
-            outlier_radius: 2.0,
-            styles: vec![
-                ".box-plot{fill:none;stroke:rgb(0,0,0);stroke-width:1;}".to_owned(),
-                ".outlier{fill:none;stroke:rgb(0,0,0);stroke-width:1;}".to_owned(),
-                ".axis{fill:none;stroke:rgb(0,0,0);stroke-width:1;}".to_owned(),
-                ".labels{fill:rgb(0,0,0);font-size:10;font-family:Arial}".to_owned(),
-                ".y-labels{text-anchor:end;}".to_owned(),
-                ".title{font-family:Arial;font-size:12;text-anchor:middle;}".to_owned(),
-            ],
-            quartile_tuples,
-        })
+fn parse_dash(s: &str) -> Result<String, String> {
+    let lengths: Vec<&str> = s
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|n| !n.is_empty())
+        .collect();
+
+    if !lengths.is_empty()
+        && lengths
+            .iter()
+            .all(|n| n.parse::<f64>().is_ok_and(|n| n >= 0.0))
+    {
+        Ok(lengths.join(","))
+    } else {
+        Err(format!(
+            "'{}' must be comma separated dash and gap lengths",
+            s
+        ))
     }
+}
 
-    fn render_chart(self: &Self, rd: &RenderData) -> Result<Document, Box<dyn Error>> {
-        let width = rd.gutter.left
-            + ((rd.quartile_tuples.len() as f64) * rd.box_plot_width)
-            + rd.gutter.right;
-        let height = rd.gutter.top + rd.gutter.bottom + rd.y_axis_height;
-        let num_y_axis_labels =
-            ((rd.y_axis_range.1 - rd.y_axis_range.0) / rd.y_axis_interval) as usize + 1;
-        let y_scale = rd.y_axis_height / (rd.y_axis_range.1 - rd.y_axis_range.0);
-        let mut document = Document::new()
-            .set("xmlns", "http://www.w3.org/2000/svg")
-            .set("width", width)
-            .set("height", height)
-            .set("viewBox", format!("0 0 {} {}", width, height))
-            .set("style", "background-color: white;");
-        let style = element::Style::new(rd.styles.join("\n"));
-        let axis = element::Polyline::new().set("class", "axis").set(
-            "points",
-            vec![
-                (rd.gutter.left, rd.gutter.top),
-                (rd.gutter.left, rd.gutter.top + rd.y_axis_height),
-                (width - rd.gutter.right, rd.gutter.top + rd.y_axis_height),
-            ],
-        );
-        let mut x_axis_labels = element::Group::new().set("class", "labels");
+/// The value of an option in a config file
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ConfigValue {
+    Flag(bool),
+    Number(f64),
+    Text(String),
+    List(Vec<ConfigValue>),
+}
 
-        for i in 0..rd.quartile_tuples.len() {
-            x_axis_labels.append(
-                element::Text::new(format!("{}", rd.quartile_tuples[i].0)).set(
-                    "transform",
-                    format!(
-                        "translate({},{}) rotate(45)",
-                        rd.gutter.left + (i as f64 * rd.box_plot_width) + rd.box_plot_width / 2.0,
-                        height - rd.gutter.bottom + 15.0
-                    ),
-                ),
-            );
-        }
+impl fmt::Display for ConfigValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigValue::Flag(flag) => write!(f, "{}", flag),
+            ConfigValue::Number(n) => write!(f, "{}", n),
+            ConfigValue::Text(text) => write!(f, "{}", text),
+            ConfigValue::List(values) => {
+                let values: Vec<String> = values.iter().map(ConfigValue::to_string).collect();
 
-        let mut y_axis_labels = element::Group::new().set("class", "labels y-labels");
+                write!(f, "{}", values.join(","))
+            }
+        }
+    }
+}
 
-        for i in 0..num_y_axis_labels {
-            let n = i as f64 * rd.y_axis_interval;
+impl Cli {
+    /// `args` with the options of any `--config` file inserted before them, leaving out those
+    /// that `args` already gives
+    fn add_config_args(args: Vec<OsString>) -> Result<Vec<OsString>, Box<dyn Error>> {
+        // Invalid arguments are reported when they are parsed again
+        let matches = match Cli::command().try_get_matches_from(&args) {
+            Ok(matches) => matches,
+            Err(_) => return Ok(args),
+        };
+        let path = match Cli::from_arg_matches(&matches)?.config {
+            Some(path) => path,
+            None => return Ok(args),
+        };
+        let content = std::fs::read_to_string(&path).context(format!(
+            "Unable to read config file '{}'",
+            path.to_string_lossy()
+        ))?;
+        let config: BTreeMap<String, ConfigValue> = json5::from_str(&content)?;
+        let command = Cli::command();
+        let mut config_args = vec![];
 
-            y_axis_labels.append(
-                element::Text::new(format!(
-                    "{0:.1$}",
-                    n + rd.y_axis_range.0,
-                    rd.y_axis_decimal_places
-                ))
-                .set(
-                    "transform",
+        for (name, value) in config {
+            let arg = command
+                .get_arguments()
+                .find(|arg| arg.get_long() == Some(name.as_str()) && name != "config")
+                .ok_or_else(|| {
                     format!(
-                        "translate({},{})",
-                        rd.gutter.left - 10.0,
-                        height - rd.gutter.bottom - f64::floor(n * y_scale) + 5.0
-                    ),
-                ),
-            );
+                        "Unknown option '{}' in config file '{}'",
+                        name,
+                        path.to_string_lossy()
+                    )
+                })?;
+
+            if matches.value_source(arg.get_id()) == Some(ValueSource::CommandLine) {
+                continue;
+            }
+
+            match value {
+                ConfigValue::Flag(true) => config_args.push(OsString::from(format!("--{}", name))),
+                // A flag cannot be turned off, so leave it out of the file instead
+                ConfigValue::Flag(false) => {
+                    return Err(From::from(format!(
+                        "Option '{}' in config file '{}' can only be true",
+                        name,
+                        path.to_string_lossy()
+                    )))
+                }
+                value => config_args.push(OsString::from(format!("--{}={}", name, value))),
+            }
         }
 
-        let mut box_plots = element::Group::new();
+        let mut args = args.into_iter();
 
-        for i in 0..rd.quartile_tuples.len() {
-            let quartile = &rd.quartile_tuples[i].1;
-            let box_width = rd.box_plot_width / 3.0;
-            let half_box_width = box_width / 2.0;
-            let whisker_width = rd.box_plot_width / 4.0;
-            let half_whisker_width = whisker_width / 2.0;
-
-            let y = vec![
-                quartile.max_before_upper_fence(),
-                quartile.upper_median(),
-                quartile.median(),
-                quartile.lower_median(),
-                quartile.min_before_lower_fence(),
-            ]
-            .iter()
-            .map(|n: &f64| -> f64 { height - rd.gutter.bottom - (n - rd.y_axis_range.0) * y_scale })
-            .collect::<Vec<f64>>();
-            let x = rd.gutter.left + rd.box_plot_width / 2.0 + (i as f64 * rd.box_plot_width);
-            let y_outliers: Vec<f64> = quartile
-                .upper_outliers()
-                .into_iter()
-                .chain(quartile.lower_outliers())
-                .collect();
-            let mut box_plot = element::Group::new().set("class", "box-plot");
+        Ok(args
+            .next()
+            .into_iter()
+            .chain(config_args)
+            .chain(args)
+            .collect())
+    }
 
-            for outlier in y_outliers.iter() {
-                box_plot.append(
-                    element::Circle::new()
-                        .set("class", "outliers")
-                        .set("cx", x)
-                        .set(
-                            "cy",
-                            height - rd.gutter.bottom - (outlier - rd.y_axis_range.0) * y_scale,
-                        )
-                        .set("r", rd.outlier_radius),
-                )
+    fn get_output(&self, output_file: Option<&Path>) -> Result<Box<dyn Write>, Box<dyn Error>> {
+        match output_file {
+            Some(path) => {
+                self.ensure_parent_dir(path)?;
+
+                File::create(path)
+                    .context(format!(
+                        "Unable to create file '{}'",
+                        path.to_string_lossy()
+                    ))
+                    .map(|f| Box::new(f) as Box<dyn Write>)
+                    .map_err(|e| Box::new(e) as Box<dyn Error>)
             }
+            None => Ok(Box::new(io::stdout())),
+        }
+    }
+
+    /// Create the missing parent directories of `path` with `--mkdir`, otherwise name them
+    fn ensure_parent_dir(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() && !dir.exists() => {
+                if self.mkdir {
+                    std::fs::create_dir_all(dir).context(format!(
+                        "Unable to create directory '{}'",
+                        dir.to_string_lossy()
+                    ))?;
+                    Ok(())
+                } else {
+                    Err(From::from(format!(
+                        "Output directory '{}' does not exist; use --mkdir to create it",
+                        dir.to_string_lossy()
+                    )))
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fold `--input` and `--output` into the positional paths they stand in for
+    fn resolve_paths(mut self) -> Result<Cli, Box<dyn Error>> {
+        if let Some(input) = self.input.take() {
+            if self.output_file.is_some() || (self.output.is_some() && self.input_file.is_some()) {
+                return Err(From::from(
+                    "An input file cannot be given both by position and with --input",
+                ));
+            }
+
+            // The only positional path is then the output
+            self.output_file = self.input_file.replace(input);
+        }
+
+        if let Some(output) = self.output.take() {
+            self.output_file = Some(output);
+        }
+
+        if self.output_file.is_none() && (self.dual_theme || self.per_page.is_some()) {
+            return Err(From::from(
+                "An output file is required for --dual-theme and --per-page",
+            ));
+        }
+
+        Ok(self)
+    }
+
+    /// Whether the arguments may render more than one document
+    fn renders_many(&self) -> bool {
+        #[cfg(feature = "watch")]
+        if self.watch {
+            return true;
+        }
+
+        self.dual_theme || self.per_page.is_some()
+    }
+
+    fn get_input(&self) -> Result<Box<dyn Read>, Box<dyn Error>> {
+        match self.input_file {
+            Some(ref path) => File::open(path)
+                .context(format!("Unable to open file '{}'", path.to_string_lossy()))
+                .map(|f| Box::new(f) as Box<dyn Read>)
+                .map_err(|e| Box::new(e) as Box<dyn Error>),
+            None => Ok(Box::new(io::stdin())),
+        }
+    }
+
+    /// The explicit `--format`, otherwise the format implied by the extension of `path`
+    fn get_input_format(&self, path: Option<&PathBuf>) -> Option<InputFormat> {
+        self.format
+            .or_else(|| path.and_then(|p| InputFormat::from_path(p)))
+    }
+
+    fn get_csv_columns(&self) -> CsvColumns {
+        CsvColumns {
+            key: self.key_column.clone(),
+            values: self.value_columns.clone(),
+        }
+    }
+
+    fn get_grid_inputs(&self) -> Result<Vec<Box<dyn Read>>, Box<dyn Error>> {
+        self.grid_inputs
+            .iter()
+            .map(|path| {
+                File::open(path)
+                    .context(format!("Unable to open file '{}'", path.to_string_lossy()))
+                    .map(|f| Box::new(f) as Box<dyn Read>)
+                    .map_err(|e| Box::new(e) as Box<dyn Error>)
+            })
+            .collect()
+    }
+
+    fn get_template(&self) -> Result<Option<String>, Box<dyn Error>> {
+        match self.template_svg {
+            Some(ref path) => std::fs::read_to_string(path)
+                .context(format!("Unable to open file '{}'", path.to_string_lossy()))
+                .map(Some)
+                .map_err(|e| Box::new(e) as Box<dyn Error>),
+            None => Ok(None),
+        }
+    }
+
+    fn get_css_path(&self, output_file: Option<&Path>) -> Result<Option<PathBuf>, Box<dyn Error>> {
+        if !self.external_css {
+            return Ok(None);
+        }
+
+        match output_file {
+            Some(path) => Ok(Some(path.with_extension("css"))),
+            None => Err(From::from("An output file is required for external CSS")),
+        }
+    }
+
+    /// The output files and their themes, which are both themes with `--dual-theme`
+    fn get_themed_outputs(&self) -> Vec<(Theme, Option<PathBuf>)> {
+        match self.output_file {
+            Some(ref path) if self.dual_theme => [Theme::Light, Theme::Dark]
+                .into_iter()
+                .map(|theme| {
+                    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                    let extension = path.extension().unwrap_or("svg".as_ref());
+                    let file_name = format!("{}-{}", stem, theme.name());
+
+                    (
+                        theme,
+                        Some(path.with_file_name(file_name).with_extension(extension)),
+                    )
+                })
+                .collect(),
+            _ => vec![(self.theme, self.output_file.clone())],
+        }
+    }
+
+    /// The output file of page number `page`, counting from 1
+    fn get_page_path(path: &Path, page: usize) -> PathBuf {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+
+        if file_name.contains("{page}") {
+            path.with_file_name(file_name.replace("{page}", &page.to_string()))
+        } else {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            let extension = path.extension().unwrap_or("svg".as_ref());
+
+            path.with_file_name(format!("{}-{}", stem, page))
+                .with_extension(extension)
+        }
+    }
+
+    fn create_file(path: &Path) -> Result<Box<dyn Write>, Box<dyn Error>> {
+        File::create(path)
+            .context(format!(
+                "Unable to create file '{}'",
+                path.to_string_lossy()
+            ))
+            .map(|f| Box::new(f) as Box<dyn Write>)
+            .map_err(|e| Box::new(e) as Box<dyn Error>)
+    }
+
+    fn get_color_map(&self) -> Result<HashMap<String, String>, Box<dyn Error>> {
+        match self.color_map {
+            Some(ref path) => {
+                let content = std::fs::read_to_string(path)
+                    .context(format!("Unable to open file '{}'", path.to_string_lossy()))?;
+
+                Ok(json5::from_str(&content)?)
+            }
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    fn get_render_options(&self) -> Result<RenderOptions, Box<dyn Error>> {
+        let opts = RenderOptions {
+            external_css: self.external_css,
+            zero_line: self.zero_line,
+            invert_y: self.invert_y,
+            include_zero: self.include_zero,
+            author: self.author.clone(),
+            source: self.source.clone(),
+            quartile_method: self.quartile_method,
+            whisker_mode: match self.whisker_percentiles {
+                Some((lower, upper)) => WhiskerMode::Percentile(lower, upper),
+                None => WhiskerMode::Tukey,
+            },
+            value_scale: self.value_scale,
+            value_offset: self.value_offset,
+            value_units: self.value_units.clone(),
+            normalize: self.normalize,
+            target_band: self.target_band,
+            outlier_radius: self.outlier_radius,
+            outlier_color: self.outlier_color.clone(),
+            density_radius: self.density_radius,
+            error_bars: self.error_bars,
+            box_guides: self.box_guides,
+            tooltips: self.tooltips,
+            zebra: self.zebra,
+            envelope: self.envelope,
+            axis_shape_rendering: self.axis_shape_rendering,
+            box_shape_rendering: self.box_shape_rendering,
+            merge_outliers: self.merge_outliers,
+            label_outliers: self.label_outliers,
+            label_iqr: self.label_iqr,
+            embed_data: self.embed_data,
+            wrap_title: self.wrap_title,
+            title_spacing: self.title_spacing,
+            no_outliers: self.no_outliers,
+            autorange_exclude_outliers: self.autorange_exclude_outliers,
+            merge_duplicates: self.merge_duplicates,
+            style: self.style,
+            median_style: self.median_style,
+            zero_spread: self.zero_spread,
+            whisker_dash: self.whisker_dash.clone(),
+            whisker_gap: self.whisker_gap,
+            median_dash: self.median_dash.clone(),
+            linejoin: self.linejoin,
+            linecap: self.linecap,
+            axis_color: self.axis_color.clone(),
+            label_color: self.label_color.clone(),
+            color_map: self.get_color_map()?,
+            stable_colors: self.stable_colors,
+            scale: self.scale,
+            shared_y: self.shared_y,
+            theme: self.theme,
+            background: self.background.clone(),
+            background_rect: self.bg_rect,
+            responsive: self.responsive,
+            root_class: self.root_class.clone(),
+            preserve_aspect_ratio: self.preserve_aspect_ratio.clone(),
+            max_width: self.max_width,
+            min_width: self.min_width,
+            plot_aspect: self.plot_aspect,
+            with_total: self.with_total,
+            order: (!self.order.is_empty()).then(|| self.order.clone()),
+            y_ticks: (!self.y_ticks_at.is_empty()).then(|| self.y_ticks_at.clone()),
+            max_ticks: self.max_ticks,
+            y_axis_intervals: self.y_intervals,
+            y_tick_precision: self.y_tick_precision,
+            value_precision: self.value_precision,
+            significant_figures: self.sig_figs,
+            max_decimal_places: self.max_decimals,
+            downsample: self.downsample,
+            number_format: match self.locale {
+                Some(ref locale) => NumberFormat::for_locale(locale)?,
+                None => NumberFormat::default(),
+            },
+            ..Default::default()
+        };
+
+        opts.validate()?;
+
+        Ok(opts)
+    }
+}
+
+/// Options controlling how chart data is laid out and rendered
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    /// Omit the inline `<style>` element, leaving styling to an external stylesheet
+    pub external_css: bool,
+    /// Draw a horizontal baseline at zero when it is within the Y axis range
+    pub zero_line: bool,
+    /// Put the highest values at the bottom of the Y axis
+    pub invert_y: bool,
+    /// Extend the automatic Y axis range to include zero
+    pub include_zero: bool,
+    /// Author added to the SVG `<metadata>` block
+    pub author: Option<String>,
+    /// Data source added to the SVG `<metadata>` block
+    pub source: Option<String>,
+    /// How the lower and upper quartiles are calculated
+    pub quartile_method: QuartileMethod,
+    /// How whisker endpoints and outliers are determined
+    pub whisker_mode: WhiskerMode,
+    /// Factor by which every value is multiplied before computing quartiles
+    pub value_scale: Option<f64>,
+    /// Amount added to every value after scaling it
+    pub value_offset: Option<f64>,
+    /// Units of the scaled values, replacing the chart units
+    pub value_units: Option<String>,
+    /// Rescale values to percentages of a range after scaling them, with units of `%`
+    pub normalize: Option<Normalization>,
+    /// Values between which a translucent band is drawn behind the box plots
+    pub target_band: Option<(f64, f64)>,
+    /// Radius of the outlier markers, defaulting to 2
+    pub outlier_radius: Option<f64>,
+    /// Stroke color of the outlier markers, otherwise that of their box plot
+    pub outlier_color: Option<String>,
+    /// Scale outlier marker radius down as the local density of outliers increases
+    pub density_radius: bool,
+    /// Dash pattern of the whiskers, such as `4,2`
+    pub whisker_dash: Option<String>,
+    /// Space left between the whiskers and the box, defaulting to none
+    pub whisker_gap: Option<f64>,
+    /// Dash pattern of the median line, such as `2,2`
+    pub median_dash: Option<String>,
+    /// `stroke-linejoin` of the box plot lines
+    pub linejoin: Option<LineJoin>,
+    /// `stroke-linecap` of the box plot lines
+    pub linecap: Option<LineCap>,
+    /// Stroke color of the axis lines, otherwise that of the theme
+    pub axis_color: Option<String>,
+    /// Fill color of the axis labels, otherwise that of the theme
+    pub label_color: Option<String>,
+    /// Draw the mean plus and minus one sample standard deviation beside each box plot
+    pub error_bars: bool,
+    /// Draw faint guides extending beyond each box at its quartiles and median
+    pub box_guides: bool,
+    /// Add a `<title>` tooltip to each box plot giving its median and quartiles
+    pub tooltips: bool,
+    /// Shade the background of every other category column, starting with the first
+    pub zebra: bool,
+    /// Draw faint lines joining the highest and lowest points of adjacent box plots
+    pub envelope: bool,
+    /// `shape-rendering` hint for the axes and zero line
+    pub axis_shape_rendering: Option<ShapeRendering>,
+    /// `shape-rendering` hint for the box plots
+    pub box_shape_rendering: Option<ShapeRendering>,
+    /// Draw coincident outliers as one marker whose opacity increases with their count
+    pub merge_outliers: bool,
+    /// Print the value of each outlier next to its marker
+    pub label_outliers: bool,
+    /// Print the interquartile range of each box beside it
+    pub label_iqr: bool,
+    /// Add the sorted values of each box to its group as a JSON `data-values` attribute
+    pub embed_data: bool,
+    /// Wrap a title wider than the chart onto several lines and deepen the top gutter to fit
+    pub wrap_title: bool,
+    /// Space between the baseline of the title and the top of the plot.  The title is then
+    /// placed a fixed distance from the top of the chart and the top gutter is sized to fit.
+    pub title_spacing: Option<f64>,
+    /// Omit the outlier markers, leaving the whiskers at the fences
+    pub no_outliers: bool,
+    /// Fit the automatic Y axis range to the whiskers rather than the outliers
+    pub autorange_exclude_outliers: bool,
+    /// Combine the values of categories with the same key into a single box, keeping the
+    /// position and other fields of the first of them
+    pub merge_duplicates: bool,
+    /// How each category is drawn
+    pub style: PlotStyle,
+    /// How the median of each box is marked
+    pub median_style: MedianStyle,
+    /// How a category whose values are all the same is drawn
+    pub zero_spread: ZeroSpread,
+    /// Stroke colors by category key; unmapped keys use the default style
+    pub color_map: HashMap<String, String>,
+    /// Color unmapped keys from a palette by a hash of the key
+    pub stable_colors: bool,
+    /// Factor applied to the SVG width and height, defaulting to 1
+    pub scale: Option<f64>,
+    /// Give every chart in a grid the Y axis computed over all of their data
+    pub shared_y: bool,
+    /// Colors of the chart
+    pub theme: Theme,
+    /// Background color of the chart, defaulting to that of the theme
+    pub background: Option<String>,
+    /// Draw the background as a rectangle covering the chart instead of a style
+    pub background_rect: bool,
+    /// Size the SVG by its `viewBox` alone so that it scales to fit its container
+    pub responsive: bool,
+    /// Class of the root SVG element
+    pub root_class: Option<String>,
+    /// `preserveAspectRatio` of the root SVG element
+    pub preserve_aspect_ratio: Option<String>,
+    /// Value range to use for the Y axis instead of the range of the chart data
+    pub data_range: Option<(f64, f64)>,
+    /// Separators used when formatting numeric labels
+    pub number_format: NumberFormat,
+    /// Maximum scaled width of the chart, narrowing the box plots to fit
+    pub max_width: Option<f64>,
+    /// Fit within `max_width` by keeping an evenly spaced sample of categories instead
+    pub downsample: bool,
+    /// Minimum scaled width of the chart, widening the side gutters equally to reach it
+    pub min_width: Option<f64>,
+    /// Add a box plot of the values of all drawn items combined after the others, with the
+    /// key "All", or "All 2" and so on if a category already has that key
+    pub with_total: bool,
+    /// Keys of the items to draw first, in order, followed by any others in input order
+    pub order: Option<Vec<String>>,
+    /// Values at which to label the Y axis instead of uniform intervals
+    pub y_ticks: Option<Vec<f64>>,
+    /// Most of `y_ticks` to draw, evenly thinned, defaulting to 100
+    pub max_ticks: Option<usize>,
+    /// Decimal places of the Y axis tick labels, derived from the tick interval if missing
+    pub y_tick_precision: Option<usize>,
+    /// Decimal places of value labels and tooltips, derived from the values if missing
+    pub value_precision: Option<usize>,
+    /// Significant figures of all numeric labels, overriding their decimal places
+    pub significant_figures: Option<usize>,
+    /// Decimal places beyond which labels use scientific notation, defaulting to 6
+    pub max_decimal_places: Option<usize>,
+    /// Width allotted to each box plot, defaulting to 60
+    pub box_plot_width: Option<f64>,
+    /// Height of the Y axis, defaulting to 400
+    pub y_axis_height: Option<f64>,
+    /// Most intervals into which the Y axis is divided, derived from its height if missing
+    pub y_axis_intervals: Option<usize>,
+    /// Width to height ratio of the plot area, from which the Y axis height is derived
+    pub plot_aspect: Option<(f64, f64)>,
+    /// Space around the plot area, defaulting to `Gutter::default()`
+    pub gutter: Option<Gutter>,
+}
+
+impl RenderOptions {
+    /// Check the options that are invalid whatever the chart data
+    pub fn validate(&self) -> Result<(), Box<dyn Error>> {
+        validate_layout(
+            self.box_plot_width.unwrap_or(DEFAULT_BOX_PLOT_WIDTH),
+            self.y_axis_height.unwrap_or(DEFAULT_Y_AXIS_HEIGHT),
+            &self.gutter.unwrap_or_default(),
+        )?;
+
+        if self.significant_figures == Some(0) {
+            return Err(From::from("Labels need at least one significant figure"));
+        }
+
+        if let Some(scale) = self
+            .scale
+            .filter(|scale| !(scale.is_finite() && *scale > 0.0))
+        {
+            return Err(From::from(format!(
+                "Scale {} must be greater than zero",
+                scale
+            )));
+        }
+
+        if let Some(whisker_gap) = self.whisker_gap.filter(|gap| *gap < 0.0 || gap.is_nan()) {
+            return Err(From::from(format!(
+                "Whisker gap {} must be zero or more",
+                whisker_gap
+            )));
+        }
+
+        if let Some(spacing) = self
+            .title_spacing
+            .filter(|spacing| *spacing < 0.0 || spacing.is_nan())
+        {
+            return Err(From::from(format!(
+                "Title spacing {} must be zero or more",
+                spacing
+            )));
+        }
+
+        if let (Some(min_width), Some(max_width)) = (self.min_width, self.max_width) {
+            if max_width < min_width {
+                return Err(From::from(format!(
+                    "Minimum width {} is greater than the maximum width {}",
+                    min_width, max_width
+                )));
+            }
+        }
+
+        if self.y_axis_intervals == Some(0) {
+            return Err(From::from("The Y axis needs at least one interval"));
+        }
+
+        if self.max_ticks == Some(0) {
+            return Err(From::from(
+                "The Y axis needs a maximum of at least one tick",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ChartData {
+    pub title: String,
+    pub units: String,
+    /// Either an array of items or a map of item keys to their values
+    #[serde(deserialize_with = "deserialize_items")]
+    pub data: Vec<ItemData>,
+    /// Rendering options suggested by the data, used where the options leave them unset
+    #[serde(default)]
+    pub render: RenderHints,
+}
+
+/// Rendering options that chart data can suggest for itself
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderHints {
+    pub outlier_radius: Option<f64>,
+    pub box_plot_width: Option<f64>,
+}
+
+/// Deserialize chart items from an array of item objects, or from a map of keys to values
+/// in the order the keys appear
+fn deserialize_items<'de, D>(deserializer: D) -> Result<Vec<ItemData>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct ItemsVisitor;
+
+    impl<'de> Visitor<'de> for ItemsVisitor {
+        type Value = Vec<ItemData>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an array of items or a map of keys to values")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut items = vec![];
+
+            while let Some(item) = seq.next_element()? {
+                items.push(item);
+            }
+
+            Ok(items)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut items = vec![];
+
+            while let Some((key, Values(values))) = map.next_entry()? {
+                items.push(ItemData {
+                    key,
+                    values,
+                    ..Default::default()
+                });
+            }
+
+            Ok(items)
+        }
+    }
+
+    deserializer.deserialize_any(ItemsVisitor)
+}
+
+/// Values given as JSON numbers, integer or not, or as strings holding numbers
+#[derive(Deserialize)]
+struct Values(#[serde(deserialize_with = "deserialize_values")] Vec<f64>);
+
+fn deserialize_values<'de, D>(deserializer: D) -> Result<Vec<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct Value(f64);
+
+    impl<'de> Deserialize<'de> for Value {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct ValueVisitor;
+
+            impl Visitor<'_> for ValueVisitor {
+                type Value = Value;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a number or a string holding a number")
+                }
+
+                fn visit_i64<E: de::Error>(self, n: i64) -> Result<Self::Value, E> {
+                    Ok(Value(n as f64))
+                }
+
+                fn visit_u64<E: de::Error>(self, n: u64) -> Result<Self::Value, E> {
+                    Ok(Value(n as f64))
+                }
+
+                fn visit_f64<E: de::Error>(self, n: f64) -> Result<Self::Value, E> {
+                    Ok(Value(n))
+                }
+
+                fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+                    s.trim()
+                        .parse()
+                        .map(Value)
+                        .map_err(|_| E::custom(format!("value \"{}\" is not a number", s)))
+                }
+            }
+
+            deserializer.deserialize_any(ValueVisitor)
+        }
+    }
+
+    let values: Vec<Value> = Vec::deserialize(deserializer)?;
+
+    Ok(values.into_iter().map(|Value(n)| n).collect())
+}
+
+impl ChartData {
+    /// An empty chart, to which items are added with `with_item`
+    pub fn new(title: &str, units: &str) -> ChartData {
+        ChartData {
+            title: title.to_owned(),
+            units: units.to_owned(),
+            data: vec![],
+            render: Default::default(),
+        }
+    }
+
+    pub fn with_item(mut self, item: ItemData) -> Self {
+        self.data.push(item);
+        self
+    }
+
+    /// Split the items, in the order they appear, into charts of at most `per_page` items
+    pub fn pages(&self, per_page: usize) -> Result<Vec<ChartData>, Box<dyn Error>> {
+        if per_page == 0 {
+            return Err(From::from("A page needs at least one category"));
+        }
+
+        Ok(self
+            .data
+            .chunks(per_page)
+            .map(|items| ChartData {
+                title: self.title.to_owned(),
+                units: self.units.to_owned(),
+                data: items.to_vec(),
+                render: self.render,
+            })
+            .collect())
+    }
+
+    /// Check the chart data for every problem that would prevent or spoil rendering it
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        let mut keys = HashSet::new();
+
+        if self.title.trim().is_empty() {
+            errors.push(ValidationError::EmptyTitle);
+        }
+
+        if self.data.is_empty() {
+            errors.push(ValidationError::NoItems);
+        }
+
+        for item in self.data.iter() {
+            if !keys.insert(item.key.as_str()) {
+                errors.push(ValidationError::DuplicateKey(item.key.to_owned()));
+            }
+
+            if item.values.len() < 3 {
+                errors.push(ValidationError::TooFewValues {
+                    key: item.key.to_owned(),
+                    count: item.values.len(),
+                });
+            }
+
+            for (index, value) in item.values.iter().enumerate() {
+                if !value.is_finite() {
+                    errors.push(ValidationError::NonFiniteValue {
+                        key: item.key.to_owned(),
+                        index,
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// An item key with its number of lower and upper outliers
+pub type OutlierCount = (String, usize, usize);
+
+/// The number of lower and upper Tukey outliers of each item, without rendering
+pub fn outlier_counts(cd: &ChartData) -> Result<Vec<OutlierCount>, Box<dyn Error>> {
+    cd.data
+        .iter()
+        .map(|item_data| {
+            let quartile = Quartile::new(&item_data.values)?;
+
+            Ok((
+                item_data.key.to_owned(),
+                quartile.lower_outliers().len(),
+                quartile.upper_outliers().len(),
+            ))
+        })
+        .collect()
+}
+
+/// A `data:image/svg+xml;base64,` URI holding a rendered chart
+pub fn data_uri(svg: &str) -> String {
+    use base64::Engine;
+
+    format!(
+        "data:image/svg+xml;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(svg)
+    )
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ItemData {
+    pub key: String,
+    /// Numbers, or strings holding numbers such as `"12"`
+    #[serde(deserialize_with = "deserialize_values")]
+    pub values: Vec<f64>,
+    /// Opacity from 0 to 1 used to de-emphasize this item; fully opaque if missing
+    #[serde(default)]
+    pub emphasis: Option<f64>,
+    /// Units of this item's values when they differ from the chart units
+    #[serde(default)]
+    pub units: Option<String>,
+    /// Value at which to end the lower whisker instead of the computed fence
+    #[serde(default)]
+    pub whisker_low: Option<f64>,
+    /// Value at which to end the upper whisker instead of the computed fence
+    #[serde(default)]
+    pub whisker_high: Option<f64>,
+    /// Extra CSS class of this item's box plot group, for targeting it with custom styles
+    #[serde(default)]
+    pub style_class: Option<String>,
+    /// Width of this item's column relative to the others, defaulting to 1
+    #[serde(default)]
+    pub weight: Option<f64>,
+}
+
+impl ItemData {
+    pub fn new(key: &str, values: &[f64]) -> ItemData {
+        ItemData {
+            key: key.to_owned(),
+            values: values.to_vec(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Space around the plot area for the axis labels and title
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gutter {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl Default for Gutter {
+    fn default() -> Self {
+        Gutter {
+            left: 80.0,
+            top: 40.0,
+            right: 80.0,
+            bottom: 80.0,
+        }
+    }
+}
+
+/// A single box plot and how it is drawn
+#[derive(Debug)]
+pub struct BoxPlotItem {
+    key: String,
+    quartile: Quartile,
+    opacity: Option<f64>,
+    color: Option<String>,
+    units: Option<String>,
+    whisker_low: Option<f64>,
+    whisker_high: Option<f64>,
+    style_class: Option<String>,
+    weight: Option<f64>,
+    total: bool,
+}
+
+impl BoxPlotItem {
+    pub fn new(key: &str, quartile: Quartile) -> BoxPlotItem {
+        BoxPlotItem {
+            key: key.to_owned(),
+            quartile,
+            opacity: None,
+            color: None,
+            units: None,
+            whisker_low: None,
+            whisker_high: None,
+            style_class: None,
+            weight: None,
+            total: false,
+        }
+    }
+
+    /// Draw the box plot with an opacity from 0 to 1
+    pub fn with_opacity(mut self, opacity: f64) -> Self {
+        self.opacity = Some(opacity.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Draw the box plot with a stroke color
+    pub fn with_color(mut self, color: &str) -> Self {
+        self.color = Some(color.to_owned());
+        self
+    }
+
+    /// Show the values of this box plot in `units` rather than the chart units
+    pub fn with_units(mut self, units: &str) -> Self {
+        self.units = Some(units.to_owned());
+        self
+    }
+
+    /// End the lower whisker at `value`, such as a known physical bound, instead of the fence
+    pub fn with_whisker_low(mut self, value: f64) -> Self {
+        self.whisker_low = Some(value);
+        self
+    }
+
+    /// End the upper whisker at `value`, such as a known physical bound, instead of the fence
+    pub fn with_whisker_high(mut self, value: f64) -> Self {
+        self.whisker_high = Some(value);
+        self
+    }
+
+    /// Add `class` to the classes of the box plot group
+    pub fn with_style_class(mut self, class: &str) -> Self {
+        self.style_class = Some(class.to_owned());
+        self
+    }
+
+    /// Give the box plot a column `weight` times as wide as that of an unweighted item, while
+    /// keeping the total width of the columns
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn quartile(&self) -> &Quartile {
+        &self.quartile
+    }
+
+    pub fn opacity(&self) -> Option<f64> {
+        self.opacity
+    }
+
+    pub fn color(&self) -> Option<&str> {
+        self.color.as_deref()
+    }
+
+    pub fn units(&self) -> Option<&str> {
+        self.units.as_deref()
+    }
+
+    pub fn style_class(&self) -> Option<&str> {
+        self.style_class.as_deref()
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.weight.unwrap_or(1.0)
+    }
+
+    /// Where the lower whisker ends, either as given or as computed by the quartile
+    pub fn whisker_low(&self) -> f64 {
+        self.whisker_low
+            .unwrap_or_else(|| self.quartile.min_before_lower_fence())
+    }
+
+    /// Where the upper whisker ends, either as given or as computed by the quartile
+    pub fn whisker_high(&self) -> f64 {
+        self.whisker_high
+            .unwrap_or_else(|| self.quartile.max_before_upper_fence())
+    }
+
+    /// Whether this box plot aggregates the values of every other item
+    pub fn is_total(&self) -> bool {
+        self.total
+    }
+}
+
+/// The fully laid out chart passed to `render_chart`.  Create one with `new`, or with
+/// `process_chart_data` from `ChartData`, then adjust it with the `with_` methods.
+#[derive(Debug)]
+pub struct RenderData {
+    title: String,
+    units: String,
+    y_axis_height: f64,
+    y_axis_intervals: Option<usize>,
+    y_axis_range: (f64, f64),
+    y_axis_interval: f64,
+    y_axis_decimal_places: usize,
+    y_ticks: Option<Vec<f64>>,
+    y_tick_precision: Option<usize>,
+    value_precision: Option<usize>,
+    significant_figures: Option<usize>,
+    max_decimal_places: usize,
+    gutter: Gutter,
+    box_plot_width: f64,
+    target_band: Option<(f64, f64)>,
+    outlier_radius: f64,
+    outlier_color: Option<String>,
+    density_radius: bool,
+    stable_colors: bool,
+    error_bars: bool,
+    box_guides: bool,
+    tooltips: bool,
+    zebra: bool,
+    envelope: bool,
+    axis_shape_rendering: Option<ShapeRendering>,
+    box_shape_rendering: Option<ShapeRendering>,
+    merge_outliers: bool,
+    label_outliers: bool,
+    label_iqr: bool,
+    embed_data: bool,
+    no_outliers: bool,
+    x_labels: bool,
+    wrap_title: bool,
+    title_spacing: Option<f64>,
+    style: PlotStyle,
+    median_style: MedianStyle,
+    zero_spread: ZeroSpread,
+    whisker_gap: f64,
+    scale: f64,
+    background: String,
+    background_rect: bool,
+    responsive: bool,
+    root_class: Option<String>,
+    preserve_aspect_ratio: Option<String>,
+    number_format: NumberFormat,
+    styles: Vec<String>,
+    inline_styles: bool,
+    zero_line: bool,
+    invert_y: bool,
+    metadata: Vec<(String, String)>,
+    items: Vec<BoxPlotItem>,
+}
+
+impl RenderData {
+    /// Lay out `items` with the default sizes and styles and a Y axis that fits their values
+    pub fn new(
+        title: &str,
+        units: &str,
+        items: Vec<BoxPlotItem>,
+    ) -> Result<RenderData, Box<dyn Error>> {
+        if items.is_empty() {
+            return Err(From::from("A chart needs at least one box plot item"));
+        }
+
+        let (y_axis_range, y_axis_interval, y_axis_decimal_places) = y_axis_scale(
+            value_range(&items, true),
+            default_y_axis_intervals(DEFAULT_Y_AXIS_HEIGHT),
+        );
+
+        Ok(RenderData {
+            title: title.to_owned(),
+            units: units.to_owned(),
+            y_axis_height: DEFAULT_Y_AXIS_HEIGHT,
+            y_axis_intervals: None,
+            y_axis_range,
+            y_axis_interval,
+            y_axis_decimal_places,
+            y_ticks: None,
+            y_tick_precision: None,
+            value_precision: None,
+            significant_figures: None,
+            max_decimal_places: DEFAULT_MAX_DECIMAL_PLACES,
+            gutter: Gutter::default(),
+            box_plot_width: DEFAULT_BOX_PLOT_WIDTH,
+            target_band: None,
+            outlier_radius: DEFAULT_OUTLIER_RADIUS,
+            outlier_color: None,
+            density_radius: false,
+            stable_colors: false,
+            error_bars: false,
+            box_guides: false,
+            tooltips: false,
+            zebra: false,
+            envelope: false,
+            axis_shape_rendering: None,
+            box_shape_rendering: None,
+            merge_outliers: false,
+            label_outliers: false,
+            label_iqr: false,
+            embed_data: false,
+            no_outliers: false,
+            x_labels: true,
+            wrap_title: false,
+            title_spacing: None,
+            style: PlotStyle::Box,
+            median_style: MedianStyle::Line,
+            zero_spread: ZeroSpread::Line,
+            whisker_gap: 0.0,
+            scale: 1.0,
+            background: Theme::default().background().to_owned(),
+            background_rect: false,
+            responsive: false,
+            root_class: None,
+            preserve_aspect_ratio: None,
+            number_format: NumberFormat::default(),
+            styles: Self::default_styles(Theme::default()),
+            inline_styles: true,
+            zero_line: false,
+            invert_y: false,
+            metadata: vec![],
+            items,
+        })
+    }
+
+    fn default_styles(theme: Theme) -> Vec<String> {
+        let fg = theme.foreground();
+
+        vec![
+            format!(".box-plot{{fill:none;stroke:{};stroke-width:1;}}", fg),
+            format!(".outlier{{fill:none;stroke:{};stroke-width:1;}}", fg),
+            format!(".axis{{fill:none;stroke:{};stroke-width:1;}}", fg),
+            format!(".labels{{fill:{};font-size:10;font-family:Arial}}", fg),
+            ".y-labels{text-anchor:end;}".to_owned(),
+            format!(
+                ".title{{fill:{};font-family:Arial;font-size:12;text-anchor:middle;}}",
+                fg
+            ),
+            ".zero-line{fill:none;stroke:rgb(128,128,128);stroke-width:1;}".to_owned(),
+            ".target-band{fill:rgb(0,160,0);fill-opacity:0.15;stroke:none;}".to_owned(),
+            ".total{stroke-width:2;}".to_owned(),
+            ".error-bar{fill:none;stroke:rgb(200,0,0);stroke-width:1;}".to_owned(),
+            ".mean{fill:rgb(200,0,0);stroke:none;}".to_owned(),
+            format!(
+                ".iqr-labels{{fill:{};stroke:none;font-size:6;font-family:Arial}}",
+                fg
+            ),
+            ".box-guides{stroke:rgb(160,160,160);stroke-width:0.5;}".to_owned(),
+            ".zebra{fill:rgb(128,128,128);fill-opacity:0.1;stroke:none;}".to_owned(),
+            ".zero-spread .median-line{stroke-width:2;}".to_owned(),
+            ".envelope{fill:none;stroke:rgb(160,160,160);stroke-width:1;}".to_owned(),
+            format!(".legend-swatch{{fill:{};stroke:none;}}", fg),
+            format!(".median{{fill:{};stroke:none;}}", fg),
+            format!(
+                ".outlier-labels{{fill:{};stroke:none;font-size:6;font-family:Arial}}",
+                fg
+            ),
+        ]
+    }
+
+    /// Extend the Y axis to cover `range`, rounded out to whole label intervals
+    pub fn with_y_axis_range(mut self, range: (f64, f64)) -> Result<Self, Box<dyn Error>> {
+        if !(range.0.is_finite() && range.1.is_finite() && range.0 < range.1) {
+            return Err(From::from(format!(
+                "Y axis range {} to {} must be finite and ascending",
+                range.0, range.1
+            )));
+        }
+
+        if self.max_y_axis_intervals() == 0 {
+            return Err(From::from("The Y axis needs at least one interval"));
+        }
+
+        (
+            self.y_axis_range,
+            self.y_axis_interval,
+            self.y_axis_decimal_places,
+        ) = y_axis_scale(range, self.max_y_axis_intervals());
+
+        Ok(self)
+    }
+
+    /// Divide the Y axis into at most `intervals` intervals when its range is next set, which
+    /// fails for zero intervals
+    pub fn with_y_axis_intervals(mut self, intervals: usize) -> Self {
+        self.y_axis_intervals = Some(intervals);
+        self
+    }
+
+    /// Label the Y axis at `values`, those outside of the axis range being ignored
+    pub fn with_y_ticks(mut self, values: Vec<f64>) -> Self {
+        self.y_ticks = Some(values);
+        self
+    }
+
+    /// Show Y axis tick labels with `places` decimal places
+    pub fn with_y_tick_precision(mut self, places: usize) -> Self {
+        self.y_tick_precision = Some(places);
+        self
+    }
+
+    /// Show value labels and tooltips with `places` decimal places
+    pub fn with_value_precision(mut self, places: usize) -> Self {
+        self.value_precision = Some(places);
+        self
+    }
+
+    /// Show all numeric labels with `figures` significant figures instead of decimal places
+    pub fn with_significant_figures(mut self, figures: usize) -> Self {
+        self.significant_figures = Some(figures);
+        self
+    }
+
+    /// Use scientific notation for labels needing more than `places` decimal places
+    pub fn with_max_decimal_places(mut self, places: usize) -> Self {
+        self.max_decimal_places = places;
+        self
+    }
+
+    pub fn with_y_axis_height(mut self, height: f64) -> Self {
+        self.y_axis_height = height;
+        self
+    }
+
+    pub fn with_gutter(mut self, gutter: Gutter) -> Self {
+        self.gutter = gutter;
+        self
+    }
+
+    /// Wrap a title wider than the chart onto several lines.  The top gutter must leave room
+    /// for the extra lines.
+    pub fn with_wrap_title(mut self, wrap_title: bool) -> Self {
+        self.wrap_title = wrap_title;
+        self
+    }
+
+    /// Place the title a fixed distance from the top of the chart, `spacing` above the plot.
+    /// The top gutter must leave room for the title and spacing.
+    pub fn with_title_spacing(mut self, spacing: Option<f64>) -> Self {
+        self.title_spacing = spacing;
+        self
+    }
+
+    /// Draw the category labels below the X axis
+    pub fn with_x_labels(mut self, x_labels: bool) -> Self {
+        self.x_labels = x_labels;
+        self
+    }
+
+    pub fn with_box_plot_width(mut self, width: f64) -> Self {
+        self.box_plot_width = width;
+        self
+    }
+
+    /// Shade the range between two values behind the box plots
+    pub fn with_target_band(mut self, lo: f64, hi: f64) -> Self {
+        self.target_band = Some((lo, hi));
+        self
+    }
+
+    pub fn with_outlier_radius(mut self, radius: f64) -> Self {
+        self.outlier_radius = radius;
+        self
+    }
+
+    /// Stroke the outlier markers with `color` rather than the color of their box plot
+    pub fn with_outlier_color(mut self, color: &str) -> Self {
+        self.outlier_color = Some(color.to_owned());
+        self
+    }
+
+    pub fn with_density_radius(mut self, density_radius: bool) -> Self {
+        self.density_radius = density_radius;
+        self
+    }
+
+    /// Color items without their own color from a palette by a hash of their key
+    pub fn with_stable_colors(mut self, stable_colors: bool) -> Self {
+        self.stable_colors = stable_colors;
+        self
+    }
+
+    /// Draw the mean plus and minus one sample standard deviation beside each box plot
+    pub fn with_error_bars(mut self, error_bars: bool) -> Self {
+        self.error_bars = error_bars;
+        self
+    }
+
+    /// Draw faint guides extending beyond each box at its quartiles and median
+    pub fn with_box_guides(mut self, box_guides: bool) -> Self {
+        self.box_guides = box_guides;
+        self
+    }
+
+    /// Add a `<title>` tooltip to each box plot giving its median and quartiles
+    pub fn with_tooltips(mut self, tooltips: bool) -> Self {
+        self.tooltips = tooltips;
+        self
+    }
+
+    /// Shade the background of every other category column, starting with the first
+    pub fn with_zebra(mut self, zebra: bool) -> Self {
+        self.zebra = zebra;
+        self
+    }
+
+    /// Draw faint lines joining the highest and lowest points, outliers included, of adjacent
+    /// box plots
+    pub fn with_envelope(mut self, envelope: bool) -> Self {
+        self.envelope = envelope;
+        self
+    }
+
+    /// Give the axes and zero line a `shape-rendering` hint
+    pub fn with_axis_shape_rendering(mut self, hint: Option<ShapeRendering>) -> Self {
+        self.axis_shape_rendering = hint;
+        self
+    }
+
+    /// Give the box plots a `shape-rendering` hint
+    pub fn with_box_shape_rendering(mut self, hint: Option<ShapeRendering>) -> Self {
+        self.box_shape_rendering = hint;
+        self
+    }
+
+    /// Draw coincident outliers as one marker whose opacity increases with their count
+    pub fn with_merge_outliers(mut self, merge_outliers: bool) -> Self {
+        self.merge_outliers = merge_outliers;
+        self
+    }
+
+    /// Print the value of each outlier next to its marker
+    pub fn with_label_outliers(mut self, label_outliers: bool) -> Self {
+        self.label_outliers = label_outliers;
+        self
+    }
+
+    /// Print the interquartile range of each box beside it
+    pub fn with_label_iqr(mut self, label_iqr: bool) -> Self {
+        self.label_iqr = label_iqr;
+        self
+    }
+
+    /// Add the values of each box to its group as a JSON `data-values` attribute
+    pub fn with_embed_data(mut self, embed_data: bool) -> Self {
+        self.embed_data = embed_data;
+        self
+    }
+
+    pub fn with_no_outliers(mut self, no_outliers: bool) -> Self {
+        self.no_outliers = no_outliers;
+        self
+    }
+
+    pub fn with_style(mut self, style: PlotStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn with_median_style(mut self, median_style: MedianStyle) -> Self {
+        self.median_style = median_style;
+        self
+    }
+
+    pub fn with_zero_spread(mut self, zero_spread: ZeroSpread) -> Self {
+        self.zero_spread = zero_spread;
+        self
+    }
+
+    pub fn with_whisker_gap(mut self, whisker_gap: f64) -> Self {
+        self.whisker_gap = whisker_gap;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_background(mut self, color: &str) -> Self {
+        self.background = color.to_owned();
+        self
+    }
+
+    /// Draw the background as a rectangle covering the chart instead of a style
+    pub fn with_background_rect(mut self, background_rect: bool) -> Self {
+        self.background_rect = background_rect;
+        self
+    }
+
+    /// Omit the SVG width and height, leaving the `viewBox` to size the chart
+    pub fn with_responsive(mut self, responsive: bool) -> Self {
+        self.responsive = responsive;
+        self
+    }
+
+    pub fn with_root_class(mut self, root_class: Option<String>) -> Self {
+        self.root_class = root_class;
+        self
+    }
+
+    pub fn with_preserve_aspect_ratio(mut self, preserve_aspect_ratio: Option<String>) -> Self {
+        self.preserve_aspect_ratio = preserve_aspect_ratio;
+        self
+    }
+
+    pub fn with_number_format(mut self, number_format: NumberFormat) -> Self {
+        self.number_format = number_format;
+        self
+    }
+
+    /// Replace the CSS rules used to style the chart
+    pub fn with_styles(mut self, styles: Vec<String>) -> Self {
+        self.styles = styles;
+        self
+    }
+
+    /// Embed the styles in the SVG rather than leaving them for an external CSS file
+    pub fn with_inline_styles(mut self, inline_styles: bool) -> Self {
+        self.inline_styles = inline_styles;
+        self
+    }
+
+    pub fn with_zero_line(mut self, zero_line: bool) -> Self {
+        self.zero_line = zero_line;
+        self
+    }
+
+    /// Put the highest values at the bottom of the Y axis
+    pub fn with_invert_y(mut self, invert_y: bool) -> Self {
+        self.invert_y = invert_y;
+        self
+    }
+
+    /// Dublin Core name and value pairs recorded in the SVG metadata
+    pub fn with_metadata(mut self, metadata: Vec<(String, String)>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn units(&self) -> &str {
+        &self.units
+    }
+
+    pub fn y_axis_height(&self) -> f64 {
+        self.y_axis_height
+    }
+
+    /// Most intervals into which the Y axis is divided
+    pub fn max_y_axis_intervals(&self) -> usize {
+        self.y_axis_intervals
+            .unwrap_or_else(|| default_y_axis_intervals(self.y_axis_height))
+    }
+
+    /// The lowest and highest values of the Y axis, rounded out to whole label intervals
+    pub fn y_axis_range(&self) -> (f64, f64) {
+        self.y_axis_range
+    }
+
+    pub fn y_axis_interval(&self) -> f64 {
+        self.y_axis_interval
+    }
+
+    pub fn y_axis_decimal_places(&self) -> usize {
+        self.y_axis_decimal_places
+    }
+
+    pub fn y_ticks(&self) -> Option<&[f64]> {
+        self.y_ticks.as_deref()
+    }
+
+    /// Decimal places of the Y axis tick labels
+    pub fn y_tick_precision(&self) -> usize {
+        self.y_tick_precision.unwrap_or(self.y_axis_decimal_places)
+    }
+
+    /// Decimal places used to label `value`
+    pub fn value_precision(&self, value: f64) -> usize {
+        self.value_precision
+            .unwrap_or_else(|| value_places(value, self.y_axis_decimal_places))
+    }
+
+    pub fn significant_figures(&self) -> Option<usize> {
+        self.significant_figures
+    }
+
+    pub fn max_decimal_places(&self) -> usize {
+        self.max_decimal_places
+    }
+
+    /// Format `value` with `places` decimal places, or in scientific notation when that
+    /// exceeds the maximum.  Significant figures, when given, take the place of both.
+    pub fn format_number(&self, value: f64, places: usize) -> String {
+        if let Some(figures) = self.significant_figures {
+            self.number_format.format_significant(value, figures)
+        } else if places > self.max_decimal_places {
+            self.number_format.format_scientific(value, places)
+        } else {
+            self.number_format.format(value, places)
+        }
+    }
+
+    pub fn gutter(&self) -> Gutter {
+        self.gutter
+    }
+
+    pub fn x_labels(&self) -> bool {
+        self.x_labels
+    }
+
+    pub fn wrap_title(&self) -> bool {
+        self.wrap_title
+    }
+
+    pub fn title_spacing(&self) -> Option<f64> {
+        self.title_spacing
+    }
+
+    /// The lines of the title, including its units, as drawn by `render_chart`
+    pub fn title_lines(&self) -> Vec<String> {
+        let title = if self.units.is_empty() {
+            self.title.to_owned()
+        } else {
+            format!("{} ({})", &self.title, &self.units)
+        };
+
+        if self.wrap_title {
+            let max_chars = (self.size().0 - 2.0 * TITLE_MARGIN) / TITLE_CHAR_WIDTH;
+
+            wrap_text(&title, f64::max(max_chars, 1.0) as usize)
+        } else {
+            vec![title]
+        }
+    }
+
+    pub fn box_plot_width(&self) -> f64 {
+        self.box_plot_width
+    }
+
+    pub fn target_band(&self) -> Option<(f64, f64)> {
+        self.target_band
+    }
+
+    pub fn outlier_radius(&self) -> f64 {
+        self.outlier_radius
+    }
+
+    pub fn outlier_color(&self) -> Option<&str> {
+        self.outlier_color.as_deref()
+    }
+
+    pub fn density_radius(&self) -> bool {
+        self.density_radius
+    }
+
+    pub fn stable_colors(&self) -> bool {
+        self.stable_colors
+    }
+
+    pub fn error_bars(&self) -> bool {
+        self.error_bars
+    }
+
+    pub fn box_guides(&self) -> bool {
+        self.box_guides
+    }
+
+    pub fn tooltips(&self) -> bool {
+        self.tooltips
+    }
+
+    pub fn zebra(&self) -> bool {
+        self.zebra
+    }
+
+    pub fn envelope(&self) -> bool {
+        self.envelope
+    }
+
+    pub fn axis_shape_rendering(&self) -> Option<ShapeRendering> {
+        self.axis_shape_rendering
+    }
+
+    pub fn box_shape_rendering(&self) -> Option<ShapeRendering> {
+        self.box_shape_rendering
+    }
+
+    pub fn merge_outliers(&self) -> bool {
+        self.merge_outliers
+    }
+
+    pub fn label_outliers(&self) -> bool {
+        self.label_outliers
+    }
+
+    pub fn label_iqr(&self) -> bool {
+        self.label_iqr
+    }
+
+    pub fn embed_data(&self) -> bool {
+        self.embed_data
+    }
+
+    pub fn no_outliers(&self) -> bool {
+        self.no_outliers
+    }
+
+    pub fn style(&self) -> PlotStyle {
+        self.style
+    }
+
+    pub fn median_style(&self) -> MedianStyle {
+        self.median_style
+    }
+
+    pub fn zero_spread(&self) -> ZeroSpread {
+        self.zero_spread
+    }
+
+    pub fn whisker_gap(&self) -> f64 {
+        self.whisker_gap
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    pub fn background(&self) -> &str {
+        &self.background
+    }
+
+    pub fn background_rect(&self) -> bool {
+        self.background_rect
+    }
+
+    pub fn responsive(&self) -> bool {
+        self.responsive
+    }
+
+    pub fn root_class(&self) -> Option<&str> {
+        self.root_class.as_deref()
+    }
+
+    pub fn preserve_aspect_ratio(&self) -> Option<&str> {
+        self.preserve_aspect_ratio.as_deref()
+    }
+
+    pub fn number_format(&self) -> &NumberFormat {
+        &self.number_format
+    }
+
+    pub fn styles(&self) -> &[String] {
+        &self.styles
+    }
+
+    pub fn inline_styles(&self) -> bool {
+        self.inline_styles
+    }
+
+    pub fn zero_line(&self) -> bool {
+        self.zero_line
+    }
+
+    pub fn invert_y(&self) -> bool {
+        self.invert_y
+    }
+
+    pub fn metadata(&self) -> &[(String, String)] {
+        &self.metadata
+    }
+
+    pub fn items(&self) -> &[BoxPlotItem] {
+        &self.items
+    }
+
+    /// The unscaled width and height of the rendered chart
+    pub fn size(&self) -> (f64, f64) {
+        (
+            self.gutter.left
+                + ((self.items.len() as f64) * self.box_plot_width)
+                + self.gutter.right,
+            self.gutter.top + self.gutter.bottom + self.y_axis_height,
+        )
+    }
+
+    /// An empty SVG document `width` by `height` with the chart background
+    fn document(&self, width: f64, height: f64) -> Document {
+        self.document_at_scale(width, height, self.scale)
+    }
+
+    /// An empty SVG document `width` by `height`, sized at `scale`, with the chart background
+    /// and root attributes, for charts alone or composed into grids and stacks
+    fn document_at_scale(&self, width: f64, height: f64, scale: f64) -> Document {
+        let mut document = Document::new()
+            .set("xmlns", "http://www.w3.org/2000/svg")
+            .set("viewBox", format!("0 0 {} {}", width, height));
+
+        if !self.responsive {
+            document.assign("width", width * scale);
+            document.assign("height", height * scale);
+        }
+
+        if let Some(ref root_class) = self.root_class {
+            document.assign("class", root_class.to_owned());
+        }
+
+        if let Some(ref preserve_aspect_ratio) = self.preserve_aspect_ratio {
+            document.assign("preserveAspectRatio", preserve_aspect_ratio.to_owned());
+        }
+
+        if self.background_rect {
+            document.append(
+                element::Rectangle::new()
+                    .set("x", 0)
+                    .set("y", 0)
+                    .set("width", width)
+                    .set("height", height)
+                    .set("fill", self.background.to_owned()),
+            );
+        } else {
+            document.assign("style", format!("background-color: {};", self.background));
+        }
+
+        document
+    }
+
+    /// The title centered in the top gutter of a chart `width` wide
+    fn title_element(&self, width: f64) -> element::Text {
+        let title_lines = self.title_lines();
+        let title_top = if self.title_spacing.is_some() {
+            TITLE_BASELINE
+        } else {
+            (self.gutter.top - (title_lines.len() - 1) as f64 * TITLE_LINE_HEIGHT) / 2.0
+        };
+        let title = if title_lines.len() == 1 {
+            element::Text::new(title_lines[0].to_owned())
+        } else {
+            let mut title = element::Text::new("");
+
+            for (i, line) in title_lines.iter().enumerate() {
+                title.append(
+                    element::TSpan::new(line.to_owned())
+                        .set("x", width / 2.0)
+                        .set("y", title_top + i as f64 * TITLE_LINE_HEIGHT),
+                );
+            }
+
+            title
+        };
+
+        title
+            .set("class", "title")
+            .set("x", width / 2.0)
+            .set("y", title_top)
+    }
+
+    /// The box, whiskers, outliers and decorations of `item`, drawn at `item_layout` with
+    /// `y_scale` pixels per unit
+    fn box_plot_group(
+        &self,
+        item: &BoxPlotItem,
+        item_layout: &ItemLayout,
+        y_scale: f64,
+    ) -> Result<element::Group, Box<dyn Error>> {
+        let quartile = &item.quartile;
+        let box_width = item_layout.box_rect.width;
+        let half_box_width = box_width / 2.0;
+        let whisker_width = item_layout.whiskers.width;
+        let half_whisker_width = whisker_width / 2.0;
+        let y = [
+            item_layout.whiskers.top,
+            item_layout.box_rect.y,
+            item_layout.median_y,
+            item_layout.box_rect.y + item_layout.box_rect.height,
+            item_layout.whiskers.bottom,
+        ];
+        let x = item_layout.x;
+        let mut class = if item.total {
+            "box-plot total".to_owned()
+        } else {
+            "box-plot".to_owned()
+        };
+
+        // A box with no spread at all would be invisible, so its median is emphasized
+        let zero_spread = quartile.min_value() == quartile.max_value();
+
+        if zero_spread {
+            class.push_str(" zero-spread");
+        }
+
+        if let Some(ref style_class) = item.style_class {
+            class.push(' ');
+            class.push_str(style_class);
+        }
+
+        let mut box_plot = element::Group::new().set("class", class);
+
+        if let Some(opacity) = item.opacity {
+            box_plot.assign("opacity", opacity);
+        }
+
+        if self.embed_data {
+            let values = quartile.values().ok_or_else(|| {
+                format!(
+                    "Category '{}' has only a summary, so has no values to embed",
+                    item.key
+                )
+            })?;
+
+            box_plot.assign("data-values", json5::to_string(&values)?);
+        }
+
+        if self.error_bars && !quartile.has_values() {
+            return Err(From::from(format!(
+                "Category '{}' has only a summary, so has no mean or standard deviation for error bars",
+                item.key
+            )));
+        }
+
+        if let Some(hint) = self.box_shape_rendering {
+            box_plot.assign("shape-rendering", hint.name());
+        }
+
+        let color = self.item_color(item);
+
+        if let Some(color) = color {
+            box_plot.assign("style", format!("stroke:{};", color));
+        }
+
+        let units = item.units.as_deref().unwrap_or(&self.units);
+        let with_units = |n: f64| {
+            let value = self.format_number(n, self.value_precision(n));
+
+            if units.is_empty() {
+                value
+            } else {
+                format!("{} {}", value, units)
+            }
+        };
+
+        if self.tooltips {
+            let mut tooltip = element::Element::new("title");
+
+            tooltip.append(svg::node::Text::new(format!(
+                "{}: median {}, quartiles {} to {}",
+                item.key,
+                with_units(quartile.median()),
+                with_units(quartile.lower_median()),
+                with_units(quartile.upper_median())
+            )));
+            box_plot.append(tooltip);
+        }
+
+        for outlier in item_layout.outliers.iter() {
+            let mut marker = element::Circle::new()
+                .set("class", "outliers")
+                .set("cx", outlier.cx)
+                .set("cy", outlier.cy)
+                .set("r", outlier.r);
+
+            if self.merge_outliers {
+                marker.assign("opacity", merged_outlier_opacity(outlier.count));
+            }
+
+            if let Some(ref outlier_color) = self.outlier_color {
+                marker.assign("style", format!("stroke:{};", outlier_color));
+            }
+
+            box_plot.append(marker)
+        }
+
+        if self.label_outliers {
+            let y_outliers: Vec<f64> = item_layout.outliers.iter().map(|o| o.cy).collect();
+            let offsets = stagger_offsets(&y_outliers, 6.0);
+
+            for (outlier, offset) in item_layout.outliers.iter().zip(offsets) {
+                let dx = self.outlier_radius + 2.0 + 20.0 * (offset / 2) as f64;
+                let places = self.value_precision(outlier.value);
+                let (label_x, anchor) = if offset % 2 == 0 {
+                    (x + dx, "start")
+                } else {
+                    (x - dx, "end")
+                };
+
+                box_plot.append(
+                    element::Text::new(self.format_number(outlier.value, places))
+                        .set("class", "outlier-labels")
+                        .set("x", label_x)
+                        .set("y", outlier.cy + 2.0)
+                        .set("text-anchor", anchor),
+                );
+            }
+        }
+
+        if self.style != PlotStyle::Box {
+            let mut median = element::Circle::new()
+                .set("class", "median")
+                .set("cx", x)
+                .set("cy", y[2])
+                .set("r", self.box_plot_width / 12.0);
+
+            if let Some(color) = color {
+                median.assign("style", format!("fill:{};", color));
+            }
+
+            if self.style == PlotStyle::Lollipop {
+                box_plot.append(
+                    element::Line::new()
+                        .set("class", "whiskers")
+                        .set("x1", x)
+                        .set("y1", y[0])
+                        .set("x2", x)
+                        .set("y2", y[4]),
+                );
+            }
+
+            box_plot.append(median);
+        } else if zero_spread {
+            if self.zero_spread == ZeroSpread::Box {
+                box_plot.append(
+                    element::Path::new().set(
+                        "d",
+                        path::Data::new()
+                            .move_to((x - half_box_width, y[2] - ZERO_SPREAD_HALF_HEIGHT))
+                            .line_by((box_width, 0.0))
+                            .line_by((0.0, 2.0 * ZERO_SPREAD_HALF_HEIGHT))
+                            .line_by((-box_width, 0.0))
+                            .close(),
+                    ),
+                );
+            }
+
+            box_plot.append(
+                element::Path::new().set("class", "median-line").set(
+                    "d",
+                    path::Data::new()
+                        .move_to((x - half_box_width, y[2]))
+                        .line_by((box_width, 0.0)),
+                ),
+            );
+        } else {
+            // The gap never takes the whiskers past their caps
+            let top_gap = self.whisker_gap.min(y[1] - y[0]);
+            let bottom_gap = self.whisker_gap.min(y[4] - y[3]);
 
             box_plot.append(
-                element::Path::new().set(
+                element::Path::new().set("class", "whiskers").set(
                     "d",
                     path::Data::new()
                         // Top whisker
                         .move_to((x - half_whisker_width, y[0]))
                         .line_by((whisker_width, 0.0))
                         .move_by((-half_whisker_width, 0.0))
-                        .line_to((x, y[1]))
-                        // Box
-                        .move_to((x - half_box_width, y[2]))
-                        .line_to((x - half_box_width, y[1]))
-                        .line_by((box_width, 0.0))
-                        .line_to((x + half_box_width, y[2]))
-                        .line_by((-box_width, 0.0))
-                        .line_to((x - half_box_width, y[3]))
-                        .line_by((box_width, 0.0))
-                        .line_to((x + half_box_width, y[2]))
-                        // Lowel whisker
-                        .move_to((x, y[3]))
+                        .line_to((x, y[1] - top_gap))
+                        // Lower whisker
+                        .move_to((x, y[3] + bottom_gap))
                         .line_to((x, y[4]))
                         .line_by((-half_whisker_width, 0.0))
                         .line_by((whisker_width, 0.0)),
                 ),
             );
+            // The notch spans 1.58 IQR / sqrt(n) either side of the median, within the box,
+            // so a box whose sample size is unknown is drawn without one
+            let sample_size = quartile
+                .sample_size()
+                .filter(|_| self.median_style.is_notched());
+            let (notch_depth, notch_top, notch_bottom) = if let Some(n) = sample_size {
+                let half_height = 1.58 * quartile.iqr() / (n as f64).sqrt() * y_scale;
+
+                (
+                    box_width / 4.0,
+                    f64::max(y[2] - half_height, y[1]),
+                    f64::min(y[2] + half_height, y[3]),
+                )
+            } else {
+                (0.0, y[2], y[2])
+            };
+
+            let mut data = path::Data::new()
+                .move_to((x - half_box_width, y[1]))
+                .line_by((box_width, 0.0));
+
+            if notch_depth > 0.0 {
+                data = data
+                    .line_to((x + half_box_width, notch_top))
+                    .line_to((x + half_box_width - notch_depth, y[2]))
+                    .line_to((x + half_box_width, notch_bottom));
+            }
+
+            data = data
+                .line_to((x + half_box_width, y[3]))
+                .line_by((-box_width, 0.0));
+
+            if notch_depth > 0.0 {
+                data = data
+                    .line_to((x - half_box_width, notch_bottom))
+                    .line_to((x - half_box_width + notch_depth, y[2]))
+                    .line_to((x - half_box_width, notch_top));
+            }
+
+            box_plot.append(element::Path::new().set("d", data.close()));
+
+            if self.median_style.is_dot() {
+                let mut median = element::Circle::new()
+                    .set("class", "median")
+                    .set("cx", x)
+                    .set("cy", y[2])
+                    .set("r", self.box_plot_width / 12.0);
+
+                if let Some(color) = color {
+                    median.assign("style", format!("fill:{};", color));
+                }
+
+                box_plot.append(median);
+            } else {
+                box_plot.append(
+                    element::Path::new().set("class", "median-line").set(
+                        "d",
+                        path::Data::new()
+                            .move_to((x - half_box_width + notch_depth, y[2]))
+                            .line_by((box_width - 2.0 * notch_depth, 0.0)),
+                    ),
+                );
+            }
+        }
+
+        if self.label_iqr {
+            box_plot.append(
+                element::Text::new(with_units(quartile.iqr()))
+                    .set("class", "iqr-labels")
+                    .set("x", x + half_box_width + 3.0)
+                    .set("y", (y[1] + y[3]) / 2.0 + 2.0)
+                    .set("text-anchor", "start"),
+            );
+        }
+
+        if self.box_guides {
+            let overhang = half_box_width + 6.0;
+
+            for guide_y in &y[1..4] {
+                box_plot.append(
+                    element::Line::new()
+                        .set("class", "box-guides")
+                        .set("x1", x - overhang)
+                        .set("y1", *guide_y)
+                        .set("x2", x + overhang)
+                        .set("y2", *guide_y),
+                );
+            }
+        }
+
+        if let Some(ref error_bar) = item_layout.error_bar {
+            let half_width = error_bar.width / 2.0;
+
+            box_plot.append(
+                element::Path::new().set("class", "error-bar").set(
+                    "d",
+                    path::Data::new()
+                        .move_to((error_bar.x - half_width, error_bar.top))
+                        .line_by((error_bar.width, 0.0))
+                        .move_to((error_bar.x, error_bar.top))
+                        .line_to((error_bar.x, error_bar.bottom))
+                        .move_to((error_bar.x - half_width, error_bar.bottom))
+                        .line_by((error_bar.width, 0.0)),
+                ),
+            );
+            box_plot.append(
+                element::Circle::new()
+                    .set("class", "mean")
+                    .set("cx", error_bar.x)
+                    .set("cy", error_bar.mean_y)
+                    .set("r", half_width / 2.0),
+            );
+        }
+
+        Ok(box_plot)
+    }
+
+    /// The Y coordinate `offset` along the Y axis from its lowest value, which is at the
+    /// bottom of the axis unless it is inverted
+    fn axis_y(&self, offset: f64) -> f64 {
+        if self.invert_y {
+            self.gutter.top + offset
+        } else {
+            self.gutter.top + self.y_axis_height - offset
+        }
+    }
+
+    /// The stroke color of `item`, when it differs from that of the style
+    fn item_color<'a>(&'a self, item: &'a BoxPlotItem) -> Option<&'a str> {
+        item.color
+            .as_deref()
+            .or_else(|| self.stable_colors.then(|| stable_color(&item.key)))
+    }
+
+    /// Statistics of all the values of the chart together and of each category
+    pub fn summary(&self) -> ChartSummary {
+        // Categories built from a summary have no values to combine
+        let values: Option<Vec<Vec<f64>>> = self
+            .items
+            .iter()
+            .filter(|item| !item.total)
+            .map(|item| item.quartile.values())
+            .collect();
+        let global = values.map(|values| {
+            let mut values: Vec<f64> = values.concat();
+
+            values.sort_by(f64::total_cmp);
+
+            let count = values.len();
+            let median = match count {
+                0 => f64::NAN,
+                n if n % 2 == 0 => (values[n / 2 - 1] + values[n / 2]) / 2.0,
+                n => values[n / 2],
+            };
+
+            GlobalSummary {
+                count,
+                min: values.first().copied().unwrap_or(f64::NAN),
+                median,
+                max: values.last().copied().unwrap_or(f64::NAN),
+            }
+        });
+
+        ChartSummary {
+            title: self.title.to_owned(),
+            units: self.units.to_owned(),
+            global,
+            categories: self
+                .items
+                .iter()
+                .map(|item| {
+                    let quartile = &item.quartile;
+
+                    CategorySummary {
+                        key: item.key.to_owned(),
+                        count: quartile.sample_size(),
+                        min: quartile.min_value(),
+                        q1: quartile.lower_median(),
+                        median: quartile.median(),
+                        q3: quartile.upper_median(),
+                        max: quartile.max_value(),
+                        mean: quartile.mean(),
+                        lower_outliers: quartile.lower_outliers(),
+                        upper_outliers: quartile.upper_outliers(),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// The positions of the chart elements as drawn by `render_chart`
+    pub fn layout(&self) -> ChartLayout {
+        let (width, height) = self.size();
+        let num_y_axis_labels =
+            ((self.y_axis_range.1 - self.y_axis_range.0) / self.y_axis_interval) as usize + 1;
+        let y_scale = self.y_axis_height / (self.y_axis_range.1 - self.y_axis_range.0);
+        let to_y = |n: f64| self.axis_y((n - self.y_axis_range.0) * y_scale);
+        // The top and bottom of the span between two values, whichever way up the axis is
+        let to_span = |a: f64, b: f64| {
+            let (a, b) = (to_y(a), to_y(b));
+
+            (a.min(b), a.max(b))
+        };
+        let count = self.items.len() as f64;
+        let total_weight: f64 = self.items.iter().map(BoxPlotItem::weight).sum();
+        let mut preceding_weight = 0.0;
+        let ticks = match self.y_ticks {
+            Some(ref values) => values
+                .iter()
+                .filter(|value| (self.y_axis_range.0..=self.y_axis_range.1).contains(*value))
+                .map(|value| Tick {
+                    value: *value,
+                    label: self.format_number(
+                        *value,
+                        self.y_tick_precision
+                            .unwrap_or_else(|| value_places(*value, self.y_axis_decimal_places)),
+                    ),
+                    y: self.axis_y(f64::floor((value - self.y_axis_range.0) * y_scale)),
+                })
+                .collect(),
+            None => (0..num_y_axis_labels)
+                .map(|i| {
+                    let n = i as f64 * self.y_axis_interval;
+
+                    Tick {
+                        value: n + self.y_axis_range.0,
+                        label: self.format_number(n + self.y_axis_range.0, self.y_tick_precision()),
+                        y: self.axis_y(f64::floor(n * y_scale)),
+                    }
+                })
+                .collect(),
+        };
+        let items = self
+            .items
+            .iter()
+            .map(|item| {
+                let quartile = &item.quartile;
+                // Columns share the width of `count` unweighted columns in proportion to weight
+                let column_width = self.box_plot_width * (item.weight() * count / total_weight);
+                let x = self.gutter.left
+                    + column_width / 2.0
+                    + self.box_plot_width * (preceding_weight * count / total_weight);
+                let box_width = column_width / 3.0;
+
+                preceding_weight += item.weight();
+                let mut values: Vec<(f64, usize)> =
+                    if self.no_outliers || self.style == PlotStyle::Strip {
+                        vec![]
+                    } else {
+                        quartile
+                            .upper_outliers()
+                            .into_iter()
+                            .chain(quartile.lower_outliers())
+                            .map(|n| (n, 1))
+                            .collect()
+                    };
+
+                if self.merge_outliers {
+                    values.dedup_by(|next, prev| {
+                        let coincident = next.0 == prev.0;
+
+                        if coincident {
+                            prev.1 += 1;
+                        }
+
+                        coincident
+                    });
+                }
+
+                let ys: Vec<f64> = values.iter().map(|(n, _)| to_y(*n)).collect();
+                let radii = if self.density_radius {
+                    density_radii(&ys, self.outlier_radius)
+                } else {
+                    vec![self.outlier_radius; ys.len()]
+                };
+                let (box_top, box_bottom) =
+                    to_span(quartile.upper_median(), quartile.lower_median());
+                let (whisker_top, whisker_bottom) =
+                    to_span(item.whisker_high(), item.whisker_low());
+                // Without values there is no mean or standard deviation to draw
+                let error_bar = self
+                    .error_bars
+                    .then(|| {
+                        let mean = quartile.mean()?;
+                        let std_dev = quartile.std_dev(VarianceKind::Sample)?;
+                        let (top, bottom) = to_span(mean + std_dev, mean - std_dev);
+
+                        Some(ErrorBar {
+                            x: x + box_width,
+                            mean_y: to_y(mean),
+                            top,
+                            bottom,
+                            width: box_width / 2.0,
+                        })
+                    })
+                    .flatten();
+
+                ItemLayout {
+                    key: item.key.to_owned(),
+                    x,
+                    box_rect: Rect {
+                        x: x - box_width / 2.0,
+                        y: box_top,
+                        width: box_width,
+                        height: box_bottom - box_top,
+                    },
+                    median_y: to_y(quartile.median()),
+                    whiskers: Whiskers {
+                        top: whisker_top,
+                        bottom: whisker_bottom,
+                        width: column_width / 4.0,
+                    },
+                    outliers: values
+                        .into_iter()
+                        .zip(ys)
+                        .zip(radii)
+                        .map(|(((value, count), cy), r)| Outlier {
+                            value,
+                            count,
+                            cx: x,
+                            cy,
+                            r,
+                        })
+                        .collect(),
+                    error_bar,
+                }
+            })
+            .collect();
+
+        ChartLayout {
+            width,
+            height,
+            plot: Rect {
+                x: self.gutter.left,
+                y: self.gutter.top,
+                width: width - self.gutter.left - self.gutter.right,
+                height: self.y_axis_height,
+            },
+            ticks,
+            items,
+        }
+    }
+}
+
+impl<'a> BoxPlotChartTool<'a> {
+    pub fn new(log: &'a dyn BoxPlotChartLog) -> BoxPlotChartTool<'a> {
+        BoxPlotChartTool { log }
+    }
+
+    pub fn run(
+        &mut self,
+        args: impl IntoIterator<Item = std::ffi::OsString>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.run_with_hook_mut(args, |_| {})
+    }
+
+    /// Like `run`, but passes the rendered `Document` to `hook` so it can be modified
+    /// before it is written out.  The arguments must render a single document, so watch
+    /// mode, `--dual-theme` and `--per-page` need `run_with_hook_mut` instead.
+    pub fn run_with_hook<F>(
+        &mut self,
+        args: impl IntoIterator<Item = std::ffi::OsString>,
+        hook: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        F: FnOnce(&mut Document),
+    {
+        let cli = match self.parse_args(args)? {
+            Some(cli) => cli,
+            None => return Ok(()),
+        };
+
+        if cli.renders_many() {
+            return Err(BoxPlotChartError::of(ErrorKind::Usage)(From::from(
+                "Watch mode, --dual-theme and --per-page render more than one document",
+            )));
+        }
+
+        let mut hook = Some(hook);
+
+        self.render_output(&cli, &mut |document: &mut Document| {
+            if let Some(hook) = hook.take() {
+                hook(document);
+            }
+        })
+    }
+
+    /// Like `run_with_hook`, but calls `hook` for every document rendered: for each theme
+    /// with `--dual-theme`, for each page with `--per-page` and after every render in watch
+    /// mode.
+    pub fn run_with_hook_mut<F>(
+        &mut self,
+        args: impl IntoIterator<Item = std::ffi::OsString>,
+        mut hook: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(&mut Document),
+    {
+        let cli = match self.parse_args(args)? {
+            Some(cli) => cli,
+            None => return Ok(()),
+        };
+
+        self.render_output(&cli, &mut hook)?;
+
+        #[cfg(feature = "watch")]
+        if cli.watch {
+            return self.watch_output(&cli, &mut hook);
+        }
+
+        Ok(())
+    }
+
+    /// Parse the command line, after any config file, returning `None` when clap has
+    /// printed help or version information instead
+    fn parse_args(
+        &self,
+        args: impl IntoIterator<Item = std::ffi::OsString>,
+    ) -> Result<Option<Cli>, Box<dyn Error>> {
+        let args = Cli::add_config_args(args.into_iter().collect())
+            .map_err(BoxPlotChartError::of(ErrorKind::Usage))?;
+        let cli = match Cli::try_parse_from(args) {
+            Ok(m) => m,
+            Err(err) if err.use_stderr() => {
+                return Err(BoxPlotChartError::of(ErrorKind::Usage)(Box::new(err)));
+            }
+            Err(err) => {
+                output!(self.log, "{}", err.to_string());
+
+                return Ok(None);
+            }
+        };
+        let cli = cli
+            .resolve_paths()
+            .map_err(BoxPlotChartError::of(ErrorKind::Usage))?;
+
+        #[cfg(feature = "watch")]
+        if cli.watch && (cli.input_file.is_none() || cli.output_file.is_none()) {
+            return Err(BoxPlotChartError::of(ErrorKind::Usage)(From::from(
+                "Watch mode requires both an input and an output file",
+            )));
+        }
+
+        // The table is of a single chart's categories
+        if cli.output_format == OutputFormat::Text && !cli.grid_inputs.is_empty() {
+            return Err(BoxPlotChartError::of(ErrorKind::Usage)(From::from(
+                "Text output cannot be used with --grid-input",
+            )));
+        }
+
+        Ok(Some(cli))
+    }
+
+    #[cfg(feature = "watch")]
+    fn watch_output<F>(&self, cli: &Cli, hook: &mut F) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(&mut Document),
+    {
+        let (Some(input_file), Some(output_file)) = (&cli.input_file, &cli.output_file) else {
+            return Err(BoxPlotChartError::of(ErrorKind::Usage)(From::from(
+                "Watch mode requires both an input and an output file",
+            )));
+        };
+
+        output!(
+            self.log,
+            "Watching '{}' for changes",
+            input_file.to_string_lossy()
+        );
+
+        watch::watch_file(input_file, || {
+            match self.render_output(cli, hook) {
+                Ok(()) => output!(self.log, "Regenerated '{}'", output_file.to_string_lossy()),
+                Err(err) => error!(self.log, "{}", err),
+            }
+
+            Ok(true)
+        })
+        .map_err(BoxPlotChartError::of(ErrorKind::Io))
+    }
+
+    fn render_output<F>(&self, cli: &Cli, hook: &mut F) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(&mut Document),
+    {
+        let render_options = cli
+            .get_render_options()
+            .map_err(BoxPlotChartError::of(ErrorKind::Usage))?;
+        let input = cli
+            .get_input()
+            .map_err(BoxPlotChartError::of(ErrorKind::InputNotFound))?;
+        let chart_data = Self::read_chart_file(
+            input,
+            cli.get_input_format(cli.input_file.as_ref()),
+            &cli.get_csv_columns(),
+        )
+        .map_err(BoxPlotChartError::of(ErrorKind::Parse))?;
+        let grid_inputs = cli
+            .get_grid_inputs()
+            .map_err(BoxPlotChartError::of(ErrorKind::InputNotFound))?;
+        let mut charts = vec![chart_data];
+
+        for (input, path) in grid_inputs.into_iter().zip(&cli.grid_inputs) {
+            charts.push(
+                Self::read_chart_file(
+                    input,
+                    cli.get_input_format(Some(path)),
+                    &cli.get_csv_columns(),
+                )
+                .map_err(BoxPlotChartError::of(ErrorKind::Parse))?,
+            );
+        }
+
+        let pages = match cli.per_page {
+            Some(per_page) => charts[0]
+                .pages(per_page)
+                .map_err(BoxPlotChartError::of(ErrorKind::Parse))?
+                .into_iter()
+                .map(|page| vec![page])
+                .collect(),
+            None => vec![charts],
+        };
+
+        for (page, charts) in pages.iter().enumerate() {
+            self.render_page(
+                cli,
+                charts,
+                cli.per_page.map(|_| page + 1),
+                &render_options,
+                hook,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Render and write `charts`, the page numbered `page` when paginating, in each theme
+    fn render_page<F>(
+        &self,
+        cli: &Cli,
+        charts: &[ChartData],
+        page: Option<usize>,
+        render_options: &RenderOptions,
+        hook: &mut F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(&mut Document),
+    {
+        for (theme, output_file) in cli.get_themed_outputs() {
+            let output_file = match page {
+                Some(page) => output_file.map(|path| Cli::get_page_path(&path, page)),
+                None => output_file,
+            };
+            let render_options = RenderOptions {
+                theme,
+                ..render_options.clone()
+            };
+            // With several charts only the styles and background of this are used, as the
+            // outputs describing a single chart are rejected by `parse_args`
+            let render_data = self
+                .process_chart_data(&charts[0], &render_options)
+                .map_err(BoxPlotChartError::of(ErrorKind::Render))?;
+            let mut document = if cli.title_only {
+                self.render_legend(&render_data)
+            } else if charts.len() == 1 {
+                self.render_chart(&render_data)
+            } else if cli.stack {
+                self.render_stack(charts, &render_options)
+            } else {
+                self.render_grid(charts, cli.grid_columns, &render_options)
+            }
+            .map_err(BoxPlotChartError::of(ErrorKind::Render))?;
+
+            hook(&mut document);
+
+            let content = match cli
+                .get_template()
+                .map_err(BoxPlotChartError::of(ErrorKind::Usage))?
+            {
+                Some(template) => template::inject(
+                    &template,
+                    &cli.template_id,
+                    &element::Group::new().set("class", "chart").add(document),
+                )
+                .map_err(BoxPlotChartError::of(ErrorKind::Render))?,
+                None => document.to_string(),
+            };
+            let content = if cli.minify {
+                whitespace::minify(&content)
+            } else if cli.pretty {
+                whitespace::pretty(&content)
+            } else {
+                content
+            };
+
+            self.write_output(cli, output_file.as_deref(), &render_data, &content)
+                .map_err(BoxPlotChartError::of(ErrorKind::Write))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_output(
+        &self,
+        cli: &Cli,
+        output_file: Option<&Path>,
+        render_data: &RenderData,
+        content: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let writer = cli.get_output(output_file)?;
+        let stylesheet = match cli.get_css_path(output_file)? {
+            Some(path) => {
+                Self::write_css_file(Cli::create_file(&path)?, render_data)?;
+                path.file_name().map(|s| s.to_string_lossy().to_string())
+            }
+            None => None,
+        };
+
+        if let Some(ref path) = cli.layout_json {
+            cli.ensure_parent_dir(path)?;
+            Self::write_layout_file(Cli::create_file(path)?, render_data)?;
+        }
+
+        if let Some(ref path) = cli.summary_json {
+            cli.ensure_parent_dir(path)?;
+            Self::write_summary_file(Cli::create_file(path)?, render_data)?;
+        }
+
+        match cli.output_format {
+            OutputFormat::Svg => Self::write_svg_file(writer, content, stylesheet.as_deref()),
+            format => Self::write_encoded_file(writer, content, render_data, format),
+        }
+    }
+
+    fn write_encoded_file(
+        mut writer: Box<dyn Write>,
+        svg: &str,
+        rd: &RenderData,
+        format: OutputFormat,
+    ) -> Result<(), Box<dyn Error>> {
+        writer.write_all(&encode_chart(svg, rd, format)?)?;
+
+        Ok(())
+    }
+
+    fn write_layout_file(
+        mut writer: Box<dyn Write>,
+        rd: &RenderData,
+    ) -> Result<(), Box<dyn Error>> {
+        writeln!(writer, "{}", json5::to_string(&rd.layout())?)?;
+
+        Ok(())
+    }
+
+    fn write_summary_file(
+        mut writer: Box<dyn Write>,
+        rd: &RenderData,
+    ) -> Result<(), Box<dyn Error>> {
+        writeln!(writer, "{}", json5::to_string(&rd.summary())?)?;
+
+        Ok(())
+    }
+
+    fn read_chart_file(
+        mut reader: Box<dyn Read>,
+        format: Option<InputFormat>,
+        columns: &CsvColumns,
+    ) -> Result<ChartData, Box<dyn Error>> {
+        let mut content = String::new();
+
+        reader.read_to_string(&mut content)?;
+
+        match format.unwrap_or_else(|| InputFormat::sniff(&content)) {
+            InputFormat::Json5 => Ok(json5::from_str(&content)?),
+            InputFormat::Csv => Self::read_csv(&content, columns),
+        }
+    }
+
+    /// Read CSV rows of a category key and its values; blank cells are ignored.  Without
+    /// selected `columns` each row is the key followed by all of its values.
+    fn read_csv(content: &str, columns: &CsvColumns) -> Result<ChartData, Box<dyn Error>> {
+        let has_headers = columns.key.is_some() || !columns.values.is_empty();
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(has_headers)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+        let column_index = |headers: &csv::StringRecord, name: &str| {
+            headers
+                .iter()
+                .position(|header| header == name.trim())
+                .ok_or_else(|| format!("CSV has no column named '{}'", name.trim()))
+        };
+        let (key_index, value_indices) = if has_headers {
+            let headers = reader.headers()?.clone();
+            let key_index = match columns.key {
+                Some(ref name) => column_index(&headers, name)?,
+                None => 0,
+            };
+            let value_indices = if columns.values.is_empty() {
+                (0..headers.len()).filter(|i| *i != key_index).collect()
+            } else {
+                columns
+                    .values
+                    .iter()
+                    .map(|name| column_index(&headers, name))
+                    .collect::<Result<Vec<usize>, String>>()?
+            };
+
+            (key_index, Some(value_indices))
+        } else {
+            (0, None)
+        };
+        let mut data = vec![];
+
+        for record in reader.records() {
+            let record = record?;
+            let line = record.position().map_or(0, |p| p.line());
+            let key = match record.get(key_index) {
+                Some(key) if !key.is_empty() => key.to_owned(),
+                _ => continue,
+            };
+            let fields: Vec<&str> = match value_indices {
+                Some(ref indices) => indices.iter().filter_map(|i| record.get(*i)).collect(),
+                None => record.iter().skip(1).collect(),
+            };
+            let values = fields
+                .into_iter()
+                .filter(|field| !field.is_empty())
+                .map(|field| {
+                    field
+                        .parse::<f64>()
+                        .map_err(|_| format!("Invalid value '{}' on CSV line {}", field, line))
+                })
+                .collect::<Result<Vec<f64>, String>>()?;
+
+            data.push(ItemData {
+                key,
+                values,
+                ..Default::default()
+            });
+        }
+
+        Ok(ChartData {
+            title: String::new(),
+            units: String::new(),
+            data,
+            render: Default::default(),
+        })
+    }
+
+    fn write_svg_file(
+        mut writer: Box<dyn Write>,
+        content: &str,
+        stylesheet: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut content = content;
+
+        if let Some(href) = stylesheet {
+            // Processing instructions must follow the XML declaration of a template, if any
+            if content.starts_with("<?xml ") {
+                if let Some(end) = content.find("?>") {
+                    writeln!(writer, "{}", &content[..end + 2])?;
+                    content = content[end + 2..].trim_start();
+                }
+            }
+
+            writeln!(
+                writer,
+                r#"<?xml-stylesheet type="text/css" href="{}"?>"#,
+                href
+            )?;
+        }
+
+        writer.write_all(content.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Write a rendered chart to any writer, such as a `Vec<u8>` or a socket
+    pub fn write_svg<W: Write>(writer: W, document: &Document) -> Result<(), Box<dyn Error>> {
+        svg::write(writer, document)?;
+
+        Ok(())
+    }
+
+    fn write_css_file(mut writer: Box<dyn Write>, rd: &RenderData) -> Result<(), Box<dyn Error>> {
+        writeln!(writer, "{}", rd.styles.join("\n"))?;
+
+        Ok(())
+    }
+
+    /// Lay out and render `cd` using `opts`, writing it to `writer` as `format`
+    pub fn render_to<W: Write>(
+        &self,
+        mut writer: W,
+        cd: &ChartData,
+        format: OutputFormat,
+        opts: &RenderOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        let rd = self.process_chart_data(cd, opts)?;
+        let svg = self.render_chart(&rd)?.to_string();
+
+        writer.write_all(&encode_chart(&svg, &rd, format)?)?;
+
+        Ok(())
+    }
+
+    /// Lay out `cd` using `opts` and render it with `renderer`
+    pub fn render_with<R: ChartRenderer>(
+        &self,
+        renderer: &R,
+        cd: &ChartData,
+        opts: &RenderOptions,
+    ) -> Result<R::Output, Box<dyn Error>> {
+        renderer.render(&self.process_chart_data(cd, opts)?)
+    }
+
+    /// Lay out and render `cd` using `opts` as an SVG document
+    pub fn render_to_string(
+        &self,
+        cd: &ChartData,
+        opts: &RenderOptions,
+    ) -> Result<String, Box<dyn Error>> {
+        let rd = self.process_chart_data(cd, opts)?;
+
+        Ok(self.render_chart(&rd)?.to_string())
+    }
+
+    /// The Y axis range a chart of `cd` is drawn with using `opts`, for aligning other visuals
+    /// with the chart
+    pub fn y_axis_range(
+        &self,
+        cd: &ChartData,
+        opts: &RenderOptions,
+    ) -> Result<(f64, f64), Box<dyn Error>> {
+        Ok(self.process_chart_data(cd, opts)?.y_axis_range())
+    }
+
+    /// Lay out `cd` for rendering using `opts`
+    pub fn process_chart_data(
+        &self,
+        cd: &ChartData,
+        opts: &RenderOptions,
+    ) -> Result<RenderData, Box<dyn Error>> {
+        opts.validate()?;
+
+        let mut converted = None;
+
+        if opts.merge_duplicates {
+            converted = Some(merge_duplicate_keys(cd));
+        }
+
+        if opts.value_scale.is_some() || opts.value_offset.is_some() {
+            let (scale, offset) = (
+                opts.value_scale.unwrap_or(1.0),
+                opts.value_offset.unwrap_or(0.0),
+            );
+
+            converted = Some(convert_chart_data(converted.as_ref().unwrap_or(cd), |_| {
+                move |n| n * scale + offset
+            }));
+        }
+
+        if opts.normalize == Some(Normalization::PerCategory) {
+            converted = Some(convert_chart_data(
+                converted.as_ref().unwrap_or(cd),
+                |item_data| {
+                    let (min, max) = item_data
+                        .values
+                        .iter()
+                        .fold((f64::MAX, f64::MIN), |(min, max), n| {
+                            (min.min(*n), max.max(*n))
+                        });
+
+                    move |n| {
+                        if max > min {
+                            (n - min) / (max - min) * 100.0
+                        } else {
+                            0.0
+                        }
+                    }
+                },
+            ));
+        }
+
+        let cd = converted.as_ref().unwrap_or(cd);
+        let mut gutter = opts.gutter.unwrap_or_default();
+        let mut box_plot_width = opts
+            .box_plot_width
+            .or(cd.render.box_plot_width)
+            .unwrap_or(DEFAULT_BOX_PLOT_WIDTH);
+        let mut y_axis_height = opts.y_axis_height.unwrap_or(DEFAULT_Y_AXIS_HEIGHT);
+
+        validate_layout(box_plot_width, y_axis_height, &gutter)?;
+
+        let fewest = cd
+            .data
+            .iter()
+            .min_by_key(|item_data| item_data.values.len());
+        let most = cd
+            .data
+            .iter()
+            .max_by_key(|item_data| item_data.values.len());
+
+        if let (Some(fewest), Some(most)) = (fewest, most) {
+            if fewest.values.len() as f64 * MAX_VALUE_COUNT_RATIO < most.values.len() as f64 {
+                warning!(
+                    self.log,
+                    "Category '{}' has {} values but '{}' has {}; their box plots may not be comparable",
+                    fewest.key,
+                    fewest.values.len(),
+                    most.key,
+                    most.values.len()
+                );
+            }
+        }
+
+        let mut data: Vec<&ItemData> = cd.data.iter().collect();
+
+        if let Some(ref order) = opts.order {
+            let (mut listed, unlisted): (Vec<&ItemData>, Vec<&ItemData>) = data
+                .into_iter()
+                .partition(|item_data| order.contains(&item_data.key));
+
+            listed.sort_by_key(|item_data| order.iter().position(|key| *key == item_data.key));
+
+            for key in order
+                .iter()
+                .filter(|key| !listed.iter().any(|item_data| item_data.key == **key))
+            {
+                warning!(
+                    self.log,
+                    "Category '{}' in the order is not in the data",
+                    key
+                );
+            }
+
+            if !unlisted.is_empty() {
+                warning!(
+                    self.log,
+                    "Categories {} are not in the order and follow those that are",
+                    unlisted
+                        .iter()
+                        .map(|item_data| format!("'{}'", item_data.key))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+
+            data = listed.into_iter().chain(unlisted).collect();
+        }
+
+        let total_columns = if opts.with_total { 1.0 } else { 0.0 };
+
+        if let Some(max_width) = opts.max_width {
+            let available = max_width / opts.scale.unwrap_or(1.0) - gutter.left - gutter.right;
+
+            if (data.len() as f64 + total_columns) * box_plot_width > available {
+                let count = (available / box_plot_width).floor() - total_columns;
+
+                if opts.downsample && count >= 1.0 {
+                    let count = count as usize;
+
+                    warning!(
+                        self.log,
+                        "Showing {} of {} categories to fit within a width of {}",
+                        count,
+                        data.len(),
+                        max_width
+                    );
+                    data = (0..count).map(|i| data[i * data.len() / count]).collect();
+                } else if available > 0.0 && !opts.downsample {
+                    box_plot_width = available / (data.len() as f64 + total_columns);
+                } else {
+                    return Err(From::from(format!(
+                        "Chart cannot fit within a width of {}",
+                        max_width
+                    )));
+                }
+            }
+        }
+
+        let mut items: Vec<BoxPlotItem> = vec![];
+
+        for item_data in data {
+            if let Some(weight) = item_data.weight {
+                if !(weight.is_finite() && weight > 0.0) {
+                    return Err(From::from(format!(
+                        "Weight {} of '{}' must be greater than zero",
+                        weight, item_data.key
+                    )));
+                }
+            }
+
+            let quartile =
+                Quartile::with_method(&item_data.values, opts.quartile_method, opts.whisker_mode)?;
+
+            items.push(BoxPlotItem {
+                key: item_data.key.to_owned(),
+                quartile,
+                opacity: item_data.emphasis.map(|e| e.clamp(0.0, 1.0)),
+                color: opts.color_map.get(&item_data.key).cloned(),
+                units: item_data.units.clone(),
+                whisker_low: item_data.whisker_low,
+                whisker_high: item_data.whisker_high,
+                style_class: item_data.style_class.clone(),
+                weight: item_data.weight,
+                total: false,
+            });
+        }
+
+        if opts.with_total {
+            let mut values: Vec<f64> = vec![];
+
+            for item in items.iter() {
+                match item.quartile.values() {
+                    Some(item_values) => values.extend(item_values),
+                    None => {
+                        return Err(From::from(format!(
+                            "The total needs the values of '{}', which has only a summary",
+                            item.key
+                        )))
+                    }
+                }
+            }
+
+            // Number the total key when a category already has it
+            let key = (1..)
+                .map(|n| match n {
+                    1 => TOTAL_KEY.to_owned(),
+                    n => format!("{} {}", TOTAL_KEY, n),
+                })
+                .find(|key| !items.iter().any(|item| item.key == *key))
+                .unwrap_or_default();
+
+            items.push(BoxPlotItem {
+                total: true,
+                ..BoxPlotItem::new(
+                    &key,
+                    Quartile::with_method(&values, opts.quartile_method, opts.whisker_mode)?,
+                )
+            });
+        }
+
+        if let Some(min_width) = opts.min_width {
+            let width = gutter.left + items.len() as f64 * box_plot_width + gutter.right;
+            let padding = (min_width / opts.scale.unwrap_or(1.0) - width) / 2.0;
+
+            if padding > 0.0 {
+                gutter.left += padding;
+                gutter.right += padding;
+            }
+        }
+
+        if let Some((aspect_width, aspect_height)) = opts.plot_aspect {
+            y_axis_height = items.len() as f64 * box_plot_width * aspect_height / aspect_width;
+            validate_layout(box_plot_width, y_axis_height, &gutter)?;
+        }
+
+        let mut y_axis_range = opts
+            .data_range
+            .unwrap_or_else(|| value_range(&items, !opts.autorange_exclude_outliers));
+
+        if opts.error_bars && opts.data_range.is_none() {
+            // Keep the error bars on the axis when the spread exceeds the data
+            for item in items.iter() {
+                let (Some(mean), Some(std_dev)) = (
+                    item.quartile.mean(),
+                    item.quartile.std_dev(VarianceKind::Sample),
+                ) else {
+                    continue;
+                };
+
+                y_axis_range = (
+                    f64::min(y_axis_range.0, mean - std_dev),
+                    f64::max(y_axis_range.1, mean + std_dev),
+                );
+            }
+        }
+
+        if opts.include_zero {
+            y_axis_range = (f64::min(y_axis_range.0, 0.0), f64::max(y_axis_range.1, 0.0));
+        }
+
+        let max_y_axis_intervals = match opts.y_axis_intervals {
+            Some(intervals) => intervals,
+            None => default_y_axis_intervals(y_axis_height),
+        };
+        let (y_axis_range, y_axis_interval, y_axis_decimal_places) =
+            y_axis_scale(y_axis_range, max_y_axis_intervals);
+        let mut y_ticks = opts.y_ticks.clone();
+
+        if let Some(ref mut ticks) = y_ticks {
+            let max_ticks = opts.max_ticks.unwrap_or(DEFAULT_MAX_TICKS);
+
+            ticks.retain(|value| (y_axis_range.0..=y_axis_range.1).contains(value));
+            ticks.sort_by(f64::total_cmp);
+            ticks.dedup();
+
+            if ticks.len() > max_ticks {
+                warning!(
+                    self.log,
+                    "Showing {} of {} Y axis ticks",
+                    max_ticks,
+                    ticks.len()
+                );
+                *ticks = (0..max_ticks)
+                    .map(|i| ticks[i * ticks.len() / max_ticks])
+                    .collect();
+            }
+        }
+
+        let mut styles = RenderData::default_styles(opts.theme);
+
+        if let Some(ref dash) = opts.whisker_dash {
+            styles.push(format!(".whiskers{{stroke-dasharray:{};}}", dash));
+        }
+
+        if let Some(ref dash) = opts.median_dash {
+            styles.push(format!(".median-line{{stroke-dasharray:{};}}", dash));
+        }
+
+        if opts.linejoin.is_some() || opts.linecap.is_some() {
+            styles.push(format!(
+                ".box-plot{{{}{}}}",
+                opts.linejoin.map_or(String::new(), |join| format!(
+                    "stroke-linejoin:{};",
+                    join.name()
+                )),
+                opts.linecap.map_or(String::new(), |cap| format!(
+                    "stroke-linecap:{};",
+                    cap.name()
+                ))
+            ));
+        }
+
+        if let Some(ref color) = opts.axis_color {
+            styles.push(format!(".axis{{stroke:{};}}", color));
+        }
+
+        if let Some(ref color) = opts.label_color {
+            styles.push(format!(".labels{{fill:{};}}", color));
+        }
+
+        let mut metadata = vec![];
+
+        if let Some(ref author) = opts.author {
+            metadata.push(("creator".to_owned(), author.to_owned()));
+        }
+
+        if let Some(ref source) = opts.source {
+            metadata.push(("source".to_owned(), source.to_owned()));
+        }
+
+        if !metadata.is_empty() {
+            metadata.push(("date".to_owned(), chrono::Utc::now().to_rfc3339()));
+        }
+
+        let mut rd = RenderData {
+            title: cd.title.to_owned(),
+            units: match opts.normalize {
+                Some(_) => "%".to_owned(),
+                None => opts
+                    .value_units
+                    .clone()
+                    .unwrap_or_else(|| cd.units.to_owned()),
+            },
+            y_axis_height,
+            y_axis_intervals: opts.y_axis_intervals,
+            y_axis_range,
+            y_axis_interval,
+            y_axis_decimal_places,
+            y_ticks,
+            y_tick_precision: opts.y_tick_precision,
+            value_precision: opts.value_precision,
+            significant_figures: opts.significant_figures,
+            max_decimal_places: opts
+                .max_decimal_places
+                .unwrap_or(DEFAULT_MAX_DECIMAL_PLACES),
+            gutter,
+            box_plot_width,
+            target_band: opts.target_band,
+            outlier_radius: opts
+                .outlier_radius
+                .or(cd.render.outlier_radius)
+                .unwrap_or(DEFAULT_OUTLIER_RADIUS),
+            outlier_color: opts.outlier_color.clone(),
+            density_radius: opts.density_radius,
+            stable_colors: opts.stable_colors,
+            error_bars: opts.error_bars,
+            box_guides: opts.box_guides,
+            tooltips: opts.tooltips,
+            zebra: opts.zebra,
+            envelope: opts.envelope,
+            axis_shape_rendering: opts.axis_shape_rendering,
+            box_shape_rendering: opts.box_shape_rendering,
+            merge_outliers: opts.merge_outliers,
+            label_outliers: opts.label_outliers,
+            label_iqr: opts.label_iqr,
+            embed_data: opts.embed_data,
+            x_labels: true,
+            wrap_title: opts.wrap_title,
+            title_spacing: opts.title_spacing,
+            no_outliers: opts.no_outliers,
+            style: opts.style,
+            median_style: opts.median_style,
+            zero_spread: opts.zero_spread,
+            whisker_gap: opts.whisker_gap.unwrap_or(0.0),
+            scale: opts.scale.unwrap_or(1.0),
+            background: opts
+                .background
+                .clone()
+                .unwrap_or_else(|| opts.theme.background().to_owned()),
+            background_rect: opts.background_rect,
+            responsive: opts.responsive,
+            root_class: opts.root_class.clone(),
+            preserve_aspect_ratio: opts.preserve_aspect_ratio.clone(),
+            number_format: opts.number_format.clone(),
+            styles,
+            inline_styles: !opts.external_css,
+            zero_line: opts.zero_line,
+            invert_y: opts.invert_y,
+            metadata,
+            items,
+        };
+
+        let extra_title_height = (rd.title_lines().len() - 1) as f64 * TITLE_LINE_HEIGHT;
+
+        if let Some(spacing) = opts.title_spacing {
+            rd.gutter.top = TITLE_BASELINE + extra_title_height + spacing;
+        } else {
+            rd.gutter.top += extra_title_height;
+        }
+
+        Ok(rd)
+    }
+
+    /// Render several charts as a grid of small multiples with `columns` charts per row.
+    /// Each chart keeps its own axes and is placed in a cell sized to fit the largest chart.
+    pub fn render_grid(
+        &self,
+        charts: &[ChartData],
+        columns: usize,
+        opts: &RenderOptions,
+    ) -> Result<Document, Box<dyn Error>> {
+        if charts.is_empty() || columns == 0 {
+            return Err(From::from("A grid needs at least one chart and one column"));
+        }
+
+        let mut render_datas = charts
+            .iter()
+            .map(|cd| self.process_chart_data(cd, opts))
+            .collect::<Result<Vec<RenderData>, Box<dyn Error>>>()?;
+
+        if opts.shared_y {
+            let data_range = render_datas
+                .iter()
+                .map(|rd| value_range(&rd.items, !opts.autorange_exclude_outliers))
+                .fold((f64::MAX, f64::MIN), |acc, range| {
+                    (f64::min(acc.0, range.0), f64::max(acc.1, range.1))
+                });
+            let opts = RenderOptions {
+                data_range: Some(data_range),
+                ..opts.clone()
+            };
+
+            render_datas = charts
+                .iter()
+                .map(|cd| self.process_chart_data(cd, &opts))
+                .collect::<Result<Vec<RenderData>, Box<dyn Error>>>()?;
+        }
+        let (cell_width, cell_height) = render_datas.iter().fold((0.0, 0.0), |acc, rd| {
+            let (width, height) = rd.size();
+
+            (
+                f64::max(acc.0, width * rd.scale),
+                f64::max(acc.1, height * rd.scale),
+            )
+        });
+        let rows = charts.len().div_ceil(columns);
+        let width = cell_width * columns.min(charts.len()) as f64;
+        let height = cell_height * rows as f64;
+        // The cells are already scaled
+        let mut document = render_datas[0].document_at_scale(width, height, 1.0);
+
+        for (i, rd) in render_datas.iter().enumerate() {
+            let (chart_width, chart_height) = rd.size();
+            // Cells are always sized, even when the grid is responsive
+            let cell = self
+                .render_chart(rd)?
+                .set("x", (i % columns) as f64 * cell_width)
+                .set("y", (i / columns) as f64 * cell_height)
+                .set("width", chart_width * rd.scale)
+                .set("height", chart_height * rd.scale);
+
+            document.append(cell);
+        }
+
+        Ok(document)
+    }
+
+    /// Render several charts with the same keys stacked vertically, drawing the X axis labels
+    /// only below the bottom chart
+    pub fn render_stack(
+        &self,
+        charts: &[ChartData],
+        opts: &RenderOptions,
+    ) -> Result<Document, Box<dyn Error>> {
+        let render_datas = charts
+            .iter()
+            .map(|cd| self.process_chart_data(cd, opts))
+            .collect::<Result<Vec<RenderData>, Box<dyn Error>>>()?;
+        let keys = |rd: &RenderData| {
+            rd.items
+                .iter()
+                .map(|item| item.key().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        match render_datas.first() {
+            None => return Err(From::from("A stack needs at least one chart")),
+            Some(first) => {
+                if render_datas.iter().any(|rd| keys(rd) != keys(first)) {
+                    return Err(From::from("Stacked charts must all have the same keys"));
+                }
+            }
+        }
+
+        let last = render_datas.len() - 1;
+        let render_datas: Vec<RenderData> = render_datas
+            .into_iter()
+            .enumerate()
+            .map(|(i, rd)| {
+                if i == last {
+                    rd
+                } else {
+                    // The title gutter of the chart below separates the plot areas
+                    let gutter = Gutter {
+                        bottom: 0.0,
+                        ..rd.gutter
+                    };
+
+                    rd.with_gutter(gutter).with_x_labels(false)
+                }
+            })
+            .collect();
+        let width = render_datas
+            .iter()
+            .fold(0.0, |acc, rd| f64::max(acc, rd.size().0 * rd.scale));
+        let height = render_datas
+            .iter()
+            .map(|rd| rd.size().1 * rd.scale)
+            .sum::<f64>();
+        // The panels are already scaled
+        let mut document = render_datas[0].document_at_scale(width, height, 1.0);
+
+        let mut y = 0.0;
+
+        for rd in render_datas.iter() {
+            let (chart_width, chart_height) = rd.size();
+            let panel = self
+                .render_chart(rd)?
+                .set("x", 0)
+                .set("y", y)
+                .set("width", chart_width * rd.scale)
+                .set("height", chart_height * rd.scale);
+
+            document.append(panel);
+            y += chart_height * rd.scale;
+        }
+
+        Ok(document)
+    }
+
+    /// Render only the title of a laid out chart and a legend of the color of each category
+    /// beneath it, for compositing with the chart
+    pub fn render_legend(&self, rd: &RenderData) -> Result<Document, Box<dyn Error>> {
+        let longest_key = rd
+            .items
+            .iter()
+            .map(|item| item.key.chars().count())
+            .max()
+            .unwrap_or(0);
+        let width = f64::max(
+            rd.size().0,
+            LEGEND_TEXT_X + longest_key as f64 * TITLE_CHAR_WIDTH + TITLE_MARGIN,
+        );
+        let height = rd.gutter.top + rd.items.len() as f64 * LEGEND_ROW_HEIGHT + TITLE_MARGIN;
+        let mut document = rd.document(width, height);
+        let mut legend = element::Group::new().set("class", "legend");
+
+        for (i, item) in rd.items.iter().enumerate() {
+            let top = rd.gutter.top + i as f64 * LEGEND_ROW_HEIGHT;
+            let mut swatch = element::Rectangle::new()
+                .set("class", "legend-swatch")
+                .set("x", 2.0 * TITLE_MARGIN)
+                .set("y", top + 3.0)
+                .set("width", 10)
+                .set("height", 10);
+
+            if let Some(color) = rd.item_color(item) {
+                swatch.assign("style", format!("fill:{};", color));
+            }
+
+            legend.append(swatch);
+            legend.append(
+                element::Text::new(item.key.to_owned())
+                    .set("class", "labels")
+                    .set("x", LEGEND_TEXT_X)
+                    .set("y", top + 12.0),
+            );
+        }
+
+        if rd.inline_styles {
+            document.append(element::Style::new(rd.styles.join("\n")));
+        }
+
+        document.append(legend);
+        document.append(rd.title_element(width));
+
+        Ok(document)
+    }
+
+    /// Render the box plot of each category of a laid out chart as an SVG group, keyed by
+    /// category, for assembling into custom layouts.  The groups keep their positions in the
+    /// chart and rely on its styles.
+    pub fn render_fragments(
+        &self,
+        rd: &RenderData,
+    ) -> Result<HashMap<String, String>, Box<dyn Error>> {
+        validate_layout(rd.box_plot_width, rd.y_axis_height, &rd.gutter)?;
+
+        let layout = rd.layout();
+        let y_scale = rd.y_axis_height / (rd.y_axis_range.1 - rd.y_axis_range.0);
+
+        rd.items
+            .iter()
+            .zip(layout.items.iter())
+            .map(|(item, item_layout)| {
+                Ok((
+                    item.key.to_owned(),
+                    rd.box_plot_group(item, item_layout, y_scale)?.to_string(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Render a laid out chart as an SVG document
+    pub fn render_chart(&self, rd: &RenderData) -> Result<Document, Box<dyn Error>> {
+        validate_layout(rd.box_plot_width, rd.y_axis_height, &rd.gutter)?;
+
+        let (width, height) = rd.size();
+        let layout = rd.layout();
+        let y_scale = rd.y_axis_height / (rd.y_axis_range.1 - rd.y_axis_range.0);
+        let mut document = rd.document(width, height);
+        let mut axis = element::Polyline::new().set("class", "axis").set(
+            "points",
+            vec![
+                (rd.gutter.left, rd.gutter.top),
+                (rd.gutter.left, rd.gutter.top + rd.y_axis_height),
+                (width - rd.gutter.right, rd.gutter.top + rd.y_axis_height),
+            ],
+        );
+
+        if let Some(hint) = rd.axis_shape_rendering {
+            axis.assign("shape-rendering", hint.name());
+        }
+
+        let mut x_axis_labels = element::Group::new().set("class", "labels");
+
+        for item in layout.items.iter() {
+            x_axis_labels.append(element::Text::new(item.key.to_owned()).set(
+                "transform",
+                format!(
+                    "translate({},{}) rotate(45)",
+                    item.x,
+                    height - rd.gutter.bottom + 15.0
+                ),
+            ));
+        }
+
+        let mut y_axis_labels = element::Group::new().set("class", "labels y-labels");
+
+        for tick in layout.ticks.iter() {
+            y_axis_labels.append(element::Text::new(tick.label.to_owned()).set(
+                "transform",
+                format!("translate({},{})", rd.gutter.left - 10.0, tick.y + 5.0),
+            ));
+        }
+
+        let zero_line = if rd.zero_line && rd.y_axis_range.0 <= 0.0 && rd.y_axis_range.1 >= 0.0 {
+            let y = rd.axis_y((0.0 - rd.y_axis_range.0) * y_scale);
+
+            let mut line = element::Line::new()
+                .set("class", "zero-line")
+                .set("x1", rd.gutter.left)
+                .set("y1", y)
+                .set("x2", width - rd.gutter.right)
+                .set("y2", y);
+
+            if let Some(hint) = rd.axis_shape_rendering {
+                line.assign("shape-rendering", hint.name());
+            }
+
+            Some(line)
+        } else {
+            None
+        };
+
+        // Clamp the band to the Y axis, omitting it when it falls outside entirely
+        let target_band = rd.target_band.and_then(|(lo, hi)| {
+            let clamp = |n: f64| n.clamp(rd.y_axis_range.0, rd.y_axis_range.1);
+            let (lo, hi) = (clamp(lo.min(hi)), clamp(lo.max(hi)));
+            let to_y = |n: f64| rd.axis_y((n - rd.y_axis_range.0) * y_scale);
+            let (top, bottom) = (to_y(hi).min(to_y(lo)), to_y(hi).max(to_y(lo)));
+
+            (lo < hi).then(|| {
+                element::Rectangle::new()
+                    .set("class", "target-band")
+                    .set("x", rd.gutter.left)
+                    .set("y", top)
+                    .set("width", width - rd.gutter.left - rd.gutter.right)
+                    .set("height", bottom - top)
+            })
+        });
+        let mut box_plots = element::Group::new();
+
+        for (item, item_layout) in rd.items.iter().zip(layout.items.iter()) {
+            box_plots.append(rd.box_plot_group(item, item_layout, y_scale)?);
+        }
+
+        let title = rd.title_element(width);
+
+        if !rd.metadata.is_empty() {
+            let mut description = element::Element::new("rdf:Description");
+
+            for (name, value) in rd.metadata.iter() {
+                let mut entry = element::Element::new(format!("dc:{}", name));
+
+                entry.append(svg::node::Text::new(value.to_owned()));
+                description.append(entry);
+            }
+
+            let mut rdf = element::Element::new("rdf:RDF");
+            let mut metadata = element::Element::new("metadata");
+
+            rdf.assign("xmlns:rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#");
+            rdf.assign("xmlns:dc", "http://purl.org/dc/elements/1.1/");
+            rdf.append(description);
+            metadata.append(rdf);
+            document.append(metadata);
+        }
+
+        if rd.inline_styles {
+            document.append(element::Style::new(rd.styles.join("\n")));
+        }
+
+        if rd.zebra {
+            let mut stripes = element::Group::new().set("class", "zebra");
+
+            // Each box is a third of the width of its column
+            for item_layout in layout.items.iter().step_by(2) {
+                let column_width = item_layout.box_rect.width * 3.0;
+
+                stripes.append(
+                    element::Rectangle::new()
+                        .set("x", item_layout.x - column_width / 2.0)
+                        .set("y", rd.gutter.top)
+                        .set("width", column_width)
+                        .set("height", rd.y_axis_height),
+                );
+            }
+
+            document.append(stripes);
+        }
+
+        if let Some(target_band) = target_band {
+            document.append(target_band);
+        }
+
+        if let Some(zero_line) = zero_line {
+            document.append(zero_line);
+        }
+
+        document.append(axis);
+
+        if rd.x_labels {
+            document.append(x_axis_labels);
+        }
+
+        document.append(y_axis_labels);
+
+        if rd.envelope {
+            // The highest and lowest points drawn for each item, whether whisker or outlier
+            let extremes: Vec<(f64, f64, f64)> = layout
+                .items
+                .iter()
+                .map(|item_layout| {
+                    let (top, bottom) = item_layout.outliers.iter().fold(
+                        (item_layout.whiskers.top, item_layout.whiskers.bottom),
+                        |(top, bottom), outlier| (top.min(outlier.cy), bottom.max(outlier.cy)),
+                    );
+
+                    (item_layout.x, top, bottom)
+                })
+                .collect();
+            let tops: Vec<(f64, f64)> = extremes.iter().map(|(x, top, _)| (*x, *top)).collect();
+            let bottoms: Vec<(f64, f64)> = extremes
+                .iter()
+                .map(|(x, _, bottom)| (*x, *bottom))
+                .collect();
+
+            for points in [tops, bottoms] {
+                document.append(
+                    element::Polyline::new()
+                        .set("class", "envelope")
+                        .set("points", points),
+                );
+            }
+        }
+
+        document.append(box_plots);
+        document.append(title);
+
+        Ok(document)
+    }
+}
+
+/// A copy of `cd` with every value of each item, including its whisker endpoints, passed
+/// through the conversion that `converter` returns for the item
+fn convert_chart_data<F, C>(cd: &ChartData, converter: F) -> ChartData
+where
+    F: Fn(&ItemData) -> C,
+    C: Fn(f64) -> f64,
+{
+    ChartData {
+        data: cd
+            .data
+            .iter()
+            .map(|item_data| {
+                let convert = converter(item_data);
+
+                ItemData {
+                    values: item_data.values.iter().map(|n| convert(*n)).collect(),
+                    whisker_low: item_data.whisker_low.map(&convert),
+                    whisker_high: item_data.whisker_high.map(&convert),
+                    ..item_data.clone()
+                }
+            })
+            .collect(),
+        ..cd.clone()
+    }
+}
+
+/// A copy of `cd` in which the values of each key are appended to its first item
+fn merge_duplicate_keys(cd: &ChartData) -> ChartData {
+    let mut data: Vec<ItemData> = vec![];
+
+    for item_data in cd.data.iter() {
+        match data.iter_mut().find(|merged| merged.key == item_data.key) {
+            Some(merged) => merged.values.extend_from_slice(&item_data.values),
+            None => data.push(item_data.clone()),
+        }
+    }
+
+    ChartData { data, ..cd.clone() }
+}
+
+/// The smallest and largest values across all items, optionally ignoring the outliers
+fn value_range(items: &[BoxPlotItem], include_outliers: bool) -> (f64, f64) {
+    items.iter().fold((f64::MAX, f64::MIN), |acc, item| {
+        let quartile = &item.quartile;
+        let (min, max) = if include_outliers {
+            (quartile.min_value(), quartile.max_value())
+        } else {
+            quartile.whisker_bounds()
+        };
+        let (min, max) = (min.min(item.whisker_low()), max.max(item.whisker_high()));
+
+        (f64::min(acc.0, min), f64::max(acc.1, max))
+    })
+}
+
+/// Round a value range out to a whole number of intervals, returning the rounded range,
+/// the interval and the number of decimal places needed to label it.  A range with no width,
+/// such as that of a single constant category, is first widened by 10% of its value either
+/// side, or by 0.5 either side of zero.
+fn y_axis_scale(range: (f64, f64), y_axis_max_intervals: usize) -> ((f64, f64), f64, usize) {
+    let range = if range.1 > range.0 {
+        range
+    } else {
+        let half_width = if range.0 == 0.0 {
+            0.5
+        } else {
+            range.0.abs() / 10.0
+        };
+
+        (range.0 - half_width, range.1 + half_width)
+    };
+    let y_axis_interval =
+        (10.0_f64).powf(((range.1 - range.0).log10()).ceil()) / (y_axis_max_intervals as f64);
+    let decimal_places = y_axis_interval.log10();
+    let y_axis_decimal_places = if decimal_places < 0.0 {
+        decimal_places.abs().ceil() as usize
+    } else {
+        0
+    };
+
+    (
+        (
+            f64::floor(range.0 / y_axis_interval) * y_axis_interval,
+            f64::ceil(range.1 / y_axis_interval) * y_axis_interval,
+        ),
+        y_axis_interval,
+        y_axis_decimal_places,
+    )
+}
+
+/// The most intervals, giving round label values, that an axis `height` high can be divided
+/// into with about `Y_AXIS_TICK_SPACING` between ticks
+fn default_y_axis_intervals(height: f64) -> usize {
+    NICE_Y_AXIS_INTERVALS
+        .iter()
+        .rev()
+        .find(|intervals| **intervals as f64 * Y_AXIS_TICK_SPACING <= height)
+        .copied()
+        .unwrap_or(NICE_Y_AXIS_INTERVALS[0])
+}
+
+/// Interval counts that divide a power of ten into round label values
+const NICE_Y_AXIS_INTERVALS: [usize; 8] = [2, 4, 5, 10, 20, 40, 50, 100];
+
+/// Approximate space between Y axis ticks when the number of intervals is not given
+const Y_AXIS_TICK_SPACING: f64 = 20.0;
+
+/// Half the height of the box drawn around a median with no spread
+const ZERO_SPREAD_HALF_HEIGHT: f64 = 3.0;
+
+/// Key of the box plot of all values combined, numbered if a category has the same key
+const TOTAL_KEY: &str = "All";
+
+/// Most Y axis ticks drawn from `y_ticks` when no maximum is given
+const DEFAULT_MAX_TICKS: usize = 100;
+
+/// Height of the Y axis when none is given
+const DEFAULT_Y_AXIS_HEIGHT: f64 = 400.0;
+
+/// Width of the column of each box plot when none is given
+const DEFAULT_BOX_PLOT_WIDTH: f64 = 60.0;
+
+/// Radius of the outlier markers when none is given
+const DEFAULT_OUTLIER_RADIUS: f64 = 2.0;
+
+/// Most decimal places in a label before switching to scientific notation
+const DEFAULT_MAX_DECIMAL_PLACES: usize = 6;
+
+/// Ratio of the most to the fewest values in a category above which a warning is logged
+const MAX_VALUE_COUNT_RATIO: f64 = 10.0;
+
+/// Estimated width of a title character, used to wrap titles wider than the chart
+const TITLE_CHAR_WIDTH: f64 = 6.5;
+
+/// Space kept clear on either side of a wrapped title
+const TITLE_MARGIN: f64 = 10.0;
+
+/// Distance from the top of the chart to the baseline of a title placed by `title_spacing`
+const TITLE_BASELINE: f64 = 20.0;
+
+/// Distance between the baselines of the lines of a wrapped title
+const TITLE_LINE_HEIGHT: f64 = 15.0;
+
+/// Height of each category of a legend drawn by `render_legend`
+const LEGEND_ROW_HEIGHT: f64 = 16.0;
+
+/// Left edge of the category keys of a legend, beside their color swatches
+const LEGEND_TEXT_X: f64 = 36.0;
+
+/// Split `text` at whitespace into lines of at most `max_chars` characters.  A word longer
+/// than that gets a line of its own.
+fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= max_chars => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_owned()),
+        }
+    }
+
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+
+    lines
+}
+
+/// Colors assigned to categories by `--stable-colors`
+const PALETTE: [&str; 10] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+    "#9c755f", "#bab0ac",
+];
+
+/// The palette color for `key`, chosen by its FNV-1a hash so that it never depends on the
+/// position of the key or the Rust version
+fn stable_color(key: &str) -> &'static str {
+    let hash = key.bytes().fold(0xcbf29ce484222325_u64, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    });
+
+    PALETTE[(hash % PALETTE.len() as u64) as usize]
+}
+
+/// An aligned table of the five-number summary and outlier counts of each item of `rd`,
+/// with keys to the left and numbers to the right of their columns
+fn summary_table(rd: &RenderData) -> String {
+    const HEADERS: [&str; 8] = [
+        "Key",
+        "Min",
+        "Q1",
+        "Median",
+        "Q3",
+        "Max",
+        "Lower outliers",
+        "Upper outliers",
+    ];
+    let number = |n: f64| rd.format_number(n, rd.value_precision(n));
+    let rows: Vec<Vec<String>> = rd
+        .items
+        .iter()
+        .map(|item| {
+            let quartile = &item.quartile;
+
+            vec![
+                item.key.to_owned(),
+                number(quartile.min_value()),
+                number(quartile.lower_median()),
+                number(quartile.median()),
+                number(quartile.upper_median()),
+                number(quartile.max_value()),
+                quartile.lower_outliers().len().to_string(),
+                quartile.upper_outliers().len().to_string(),
+            ]
+        })
+        .collect();
+    let widths: Vec<usize> = (0..HEADERS.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .fold(HEADERS[i].len(), usize::max)
+        })
+        .collect();
+    let line = |cells: &[String]| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(widths.iter())
+            .enumerate()
+            .map(|(i, (cell, width))| {
+                if i == 0 {
+                    format!("{:<width$}", cell, width = width)
+                } else {
+                    format!("{:>width$}", cell, width = width)
+                }
+            })
+            .collect();
+
+        format!("{}\n", cells.join("  ").trim_end())
+    };
+    let headers: Vec<String> = HEADERS.iter().map(|header| header.to_string()).collect();
+    let rules: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    let mut table = line(&headers);
+
+    table.push_str(&line(&rules));
+
+    for row in rows.iter() {
+        table.push_str(&line(row));
+    }
+
+    table
+}
+
+/// Escape the characters of `text` that are special in HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Convert `svg`, rendered from `rd`, to `format`
+fn encode_chart(
+    svg: &str,
+    rd: &RenderData,
+    format: OutputFormat,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    Ok(match format {
+        OutputFormat::Svg => svg.as_bytes().to_vec(),
+        OutputFormat::Html => format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+            escape_html(&rd.title),
+            svg
+        )
+        .into_bytes(),
+        OutputFormat::DataUri => data_uri(svg).into_bytes(),
+        OutputFormat::Text => TextRenderer.render(rd)?.into_bytes(),
+        #[cfg(feature = "png")]
+        OutputFormat::Png => rasterize(svg)?,
+        #[cfg(feature = "pdf")]
+        OutputFormat::Pdf => pdf::from_svg(svg, rd.background())?,
+    })
+}
+
+/// Rasterize an SVG document to PNG at the size given by its width and height
+#[cfg(feature = "png")]
+fn rasterize(svg: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    use resvg::{tiny_skia, usvg};
+
+    let mut options = usvg::Options::default();
+
+    options.fontdb_mut().load_system_fonts();
+
+    let tree = usvg::Tree::from_str(svg, &options)?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or("Chart is too large to rasterize")?;
+
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    Ok(pixmap.encode_png()?)
+}
+
+/// Opacity of a marker standing for `count` coincident outliers
+fn merged_outlier_opacity(count: usize) -> f64 {
+    (0.25 * count as f64).min(1.0)
+}
+
+/// Decimal places needed to show `value` exactly, up to 3, but no fewer than `min`
+fn value_places(value: f64, min: usize) -> usize {
+    format!("{:.3}", value)
+        .trim_end_matches('0')
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len())
+        .max(min)
+}
+
+/// Check that the layout dimensions can produce a valid chart
+fn validate_layout(
+    box_plot_width: f64,
+    y_axis_height: f64,
+    gutter: &Gutter,
+) -> Result<(), Box<dyn Error>> {
+    if !(box_plot_width.is_finite() && box_plot_width > 0.0) {
+        return Err(From::from(format!(
+            "Box plot width {} must be greater than zero",
+            box_plot_width
+        )));
+    }
+
+    if !(y_axis_height.is_finite() && y_axis_height > 0.0) {
+        return Err(From::from(format!(
+            "Y axis height {} must be greater than zero",
+            y_axis_height
+        )));
+    }
+
+    for (name, value) in [
+        ("left", gutter.left),
+        ("top", gutter.top),
+        ("right", gutter.right),
+        ("bottom", gutter.bottom),
+    ] {
+        if !(value.is_finite() && value >= 0.0) {
+            return Err(From::from(format!(
+                "The {} gutter {} cannot be negative",
+                name, value
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// A column for the label at each of `ys` such that labels in the same column are at least
+/// `spacing` apart; even columns go to the right of the marker and odd columns to the left
+fn stagger_offsets(ys: &[f64], spacing: f64) -> Vec<usize> {
+    let mut columns: Vec<Vec<f64>> = vec![];
+
+    ys.iter()
+        .map(|y| {
+            let column = columns
+                .iter()
+                .position(|placed| placed.iter().all(|p| (p - y).abs() >= spacing))
+                .unwrap_or(columns.len());
+
+            if column == columns.len() {
+                columns.push(vec![]);
+            }
+
+            columns[column].push(*y);
+            column
+        })
+        .collect()
+}
+
+/// Shrink the radius of each outlier marker according to how many other markers lie
+/// within one marker diameter of it, so that dense clusters stay readable
+fn density_radii(ys: &[f64], radius: f64) -> Vec<f64> {
+    ys.iter()
+        .map(|y| {
+            let neighbours = ys.iter().filter(|o| (*o - y).abs() < radius * 2.0).count();
+
+            radius / (neighbours as f64).sqrt()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common::{example, TempDir};
+
+    struct TestLogger {
+        warnings: std::cell::RefCell<Vec<String>>,
+    }
+
+    impl TestLogger {
+        fn new() -> TestLogger {
+            TestLogger {
+                warnings: Default::default(),
+            }
+        }
+
+        fn warnings(&self) -> Vec<String> {
+            self.warnings.borrow().clone()
+        }
+    }
+
+    impl BoxPlotChartLog for TestLogger {
+        fn output(&self, _args: Arguments) {}
+        fn warning(&self, args: Arguments) {
+            self.warnings.borrow_mut().push(args.to_string());
+        }
+        fn error(&self, _args: Arguments) {}
+    }
+
+    fn chart_data(items: &[(&str, &[f64])]) -> ChartData {
+        items
+            .iter()
+            .fold(ChartData::new("Test", "ms"), |cd, (key, values)| {
+                cd.with_item(ItemData::new(key, values))
+            })
+    }
+
+    #[test]
+    fn basic_test() {
+        let logger = TestLogger::new();
+        let mut tool = BoxPlotChartTool::new(&logger);
+        let args: Vec<std::ffi::OsString> = vec!["".into(), "--help".into()];
+
+        tool.run(args).unwrap();
+    }
+
+    #[test]
+    fn external_css_test() {
+        let logger = TestLogger::new();
+        let mut tool = BoxPlotChartTool::new(&logger);
+        let dir = TempDir::new("external_css");
+        let svg_path = dir.join("chart.svg");
+        let args: Vec<std::ffi::OsString> = vec![
+            "".into(),
+            example().into(),
+            svg_path.clone().into(),
+            "--external-css".into(),
+        ];
+
+        tool.run(args).unwrap();
+
+        let svg = std::fs::read_to_string(&svg_path).unwrap();
+        let css = std::fs::read_to_string(dir.join("chart.css")).unwrap();
+
+        assert!(svg.starts_with(r#"<?xml-stylesheet type="text/css" href="chart.css"?>"#));
+        assert!(!svg.contains("<style>"));
+        assert!(css.contains(".box-plot{"));
+        assert!(css.contains(".title{"));
+    }
+
+    #[test]
+    fn template_stylesheet_test() {
+        let logger = TestLogger::new();
+        let mut tool = BoxPlotChartTool::new(&logger);
+        let dir = TempDir::new("template_stylesheet");
+        let template_path = dir.join("template.svg");
+        let svg_path = dir.join("chart.svg");
+
+        std::fs::write(
+            &template_path,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\"><g data-id=\"chart\"/><g id=\"chart\"/></svg>",
+        )
+        .unwrap();
+
+        let args: Vec<std::ffi::OsString> = vec![
+            "".into(),
+            example().into(),
+            svg_path.clone().into(),
+            "--external-css".into(),
+            "--template-svg".into(),
+            template_path.into(),
+        ];
+
+        tool.run(args).unwrap();
+
+        let svg = std::fs::read_to_string(&svg_path).unwrap();
+
+        assert!(svg.starts_with(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<?xml-stylesheet type=\"text/css\" href=\"chart.css\"?>\n<svg "
+        ));
+        assert!(svg.contains(r#"<g data-id="chart"/><g id="chart">"#));
+    }
+
+    #[test]
+    fn invert_y_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[0.0, 10.0, 20.0, 30.0, 40.0, 50.0])]);
+        let opts = RenderOptions {
+            invert_y: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let layout = rd.layout();
+        let item = &layout.items[0];
+        let plot_bottom = layout.plot.y + layout.plot.height;
+
+        assert_eq!(item.whiskers.bottom, plot_bottom);
+        assert_eq!(item.whiskers.top, layout.plot.y);
+        assert!(item.box_rect.height > 0.0);
+        assert_eq!(layout.ticks[0].y, layout.plot.y);
+        assert_eq!(layout.ticks.last().unwrap().y, plot_bottom);
+        assert!(layout.ticks.windows(2).all(|pair| pair[0].y < pair[1].y));
+
+        let normal = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap()
+            .layout();
+
+        assert_eq!(normal.items[0].whiskers.top, layout.plot.y);
+        assert_eq!(
+            normal.items[0].median_y - layout.plot.y,
+            plot_bottom - item.median_y
+        );
+
+        let built = RenderData::new(
+            "Test",
+            "ms",
+            vec![BoxPlotItem::new(
+                "a",
+                Quartile::new(&[0.0, 10.0, 20.0, 30.0, 40.0, 50.0]).unwrap(),
+            )],
+        )
+        .unwrap()
+        .with_invert_y(true);
+
+        assert!(built.invert_y());
+        assert!(built
+            .layout()
+            .ticks
+            .windows(2)
+            .all(|pair| pair[0].y < pair[1].y));
+    }
+
+    #[test]
+    fn zero_line_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[-10.0, -5.0, 0.0, 5.0, 10.0])]);
+        let opts = RenderOptions {
+            zero_line: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        // Zero is halfway down the 400px axis which starts 40px from the top
+        assert_eq!(rd.y_axis_range, (-10.0, 10.0));
+        assert!(document.contains(r#"class="zero-line" x1="80" x2="140" y1="240" y2="240""#));
+    }
+
+    #[test]
+    fn include_zero_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[50.0, 60.0, 70.0, 80.0])]);
+        let rd = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+
+        assert!(rd.y_axis_range.0 > 0.0);
+
+        let opts = RenderOptions {
+            include_zero: true,
+            zero_line: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(rd.y_axis_range.0, 0.0);
+        assert!(document.contains(r#"class="zero-line""#));
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn watch_error_test() {
+        let logger = TestLogger::new();
+        let mut tool = BoxPlotChartTool::new(&logger);
+        let dir = TempDir::new("watch_error");
+        let input_path = dir.join("input.json5");
+        let args: Vec<std::ffi::OsString> = vec![
+            "".into(),
+            input_path.clone().into(),
+            dir.join("chart.svg").into(),
+            "--watch".into(),
+        ];
+
+        std::fs::copy(example(), &input_path).unwrap();
+
+        // The input is gone by the time it is watched
+        let err = tool
+            .run_with_hook_mut(args, |_| std::fs::remove_file(&input_path).unwrap())
+            .unwrap_err();
+
+        assert_eq!(error::exit_code(err.as_ref()), ErrorKind::Io.exit_code());
+    }
+
+    #[test]
+    fn run_with_hook_test() {
+        let logger = TestLogger::new();
+        let mut tool = BoxPlotChartTool::new(&logger);
+        let dir = TempDir::new("run_with_hook");
+        let svg_path = dir.join("chart.svg");
+        let args: Vec<std::ffi::OsString> =
+            vec!["".into(), example().into(), svg_path.clone().into()];
+
+        tool.run_with_hook(args, |document| {
+            document.append(
+                element::Text::new("Draft")
+                    .set("class", "watermark")
+                    .set("x", 10)
+                    .set("y", 10),
+            );
+        })
+        .unwrap();
+
+        let svg = std::fs::read_to_string(&svg_path).unwrap();
+
+        assert!(svg.contains(r#"<text class="watermark" x="10" y="10">"#));
+        assert!(svg.contains("Draft"));
+
+        // A hook that can only be called once needs a single document
+        let watermark = String::from("Draft");
+        let dir = TempDir::new("run_with_hook_mut");
+        let args = |extra: &str| -> Vec<std::ffi::OsString> {
+            vec![
+                "".into(),
+                example().into(),
+                dir.join("chart.svg").into(),
+                extra.into(),
+            ]
+        };
+
+        assert!(tool
+            .run_with_hook(args("--dual-theme"), move |document| {
+                document.append(element::Text::new(watermark));
+            })
+            .is_err());
+
+        let mut renders = 0;
+
+        tool.run_with_hook_mut(args("--dual-theme"), |_| renders += 1)
+            .unwrap();
+
+        assert_eq!(renders, 2);
+    }
+
+    #[test]
+    fn metadata_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0])]);
+        let rd = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+
+        assert!(!tool
+            .render_chart(&rd)
+            .unwrap()
+            .to_string()
+            .contains("<metadata>"));
+
+        let opts = RenderOptions {
+            author: Some("Jane Doe".to_owned()),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let metadata =
+            &document[document.find("<metadata>").unwrap()..document.find("</metadata>").unwrap()];
+
+        assert!(metadata.contains("<dc:creator>Jane Doe</dc:creator>"));
+        assert!(metadata.contains("<dc:date>"));
+    }
+
+    #[test]
+    fn style_class_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let mut cd = chart_data(&[
+            ("treatment", &[1.0, 2.0, 3.0]),
+            ("control", &[2.0, 3.0, 4.0]),
+        ]);
+
+        cd.data[1].style_class = Some("control-group".to_owned());
+
+        let rd = tool
+            .process_chart_data(
+                &cd,
+                &RenderOptions {
+                    tooltips: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let control = document
+            .find(r#"<g class="box-plot control-group">"#)
+            .unwrap();
+
+        assert_eq!(document.matches("control-group").count(), 1);
+        assert!(document[control..].contains("<title>control: median"));
+        assert!(document.find("<title>treatment: median").unwrap() < control);
+    }
+
+    #[test]
+    fn y_axis_range_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[3.0, 7.0, 12.0]), ("b", &[21.0, 30.0, 47.0])]);
+
+        assert_eq!(
+            tool.y_axis_range(&cd, &RenderOptions::default()).unwrap(),
+            (0.0, 50.0)
+        );
+        assert_eq!(
+            tool.y_axis_range(
+                &cd,
+                &RenderOptions {
+                    data_range: Some((-5.0, 100.0)),
+                    ..Default::default()
+                }
+            )
+            .unwrap(),
+            (-50.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn render_legend_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("north", &[1.0, 2.0, 3.0]), ("south", &[2.0, 3.0, 4.0])]);
+        let opts = RenderOptions {
+            color_map: HashMap::from([("north".to_owned(), "rgb(255,0,0)".to_owned())]),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_legend(&rd).unwrap().to_string();
+
+        assert!(document.contains(">\nTest (ms)\n<"));
+        assert!(document.contains(r#"<g class="legend">"#));
+        assert_eq!(
+            document.matches(r#"<rect class="legend-swatch""#).count(),
+            2
+        );
+        assert!(document.contains(r#"style="fill:rgb(255,0,0);""#));
+        assert!(document.contains(">\nnorth\n<"));
+        assert!(document.contains(">\nsouth\n<"));
+        assert!(!document.contains(r#"class="box-plot"#));
+        assert!(!document.contains(r#"class="axis""#));
+        assert!(document.contains(r#"viewBox="0 0 280 82""#));
+    }
+
+    #[test]
+    fn outlier_color_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[(
+            "a",
+            &[48.0, 52.0, 57.0, 64.0, 72.0, 76.0, 77.0, 81.0, 85.0, 160.0],
+        )]);
+        let opts = RenderOptions {
+            color_map: HashMap::from([("a".to_owned(), "rgb(255,0,0)".to_owned())]),
+            outlier_color: Some("rgb(0,0,255)".to_owned()),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert!(document.contains(r#"<g class="box-plot" style="stroke:rgb(255,0,0);">"#));
+        assert_eq!(
+            document
+                .matches(r#"<circle class="outliers" cx="110" cy=""#)
+                .count(),
+            1
+        );
+        assert!(document.contains(r#"r="2" style="stroke:rgb(0,0,255);"/>"#));
+    }
+
+    #[test]
+    fn emphasis_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let mut cd = chart_data(&[("focus", &[1.0, 2.0, 3.0]), ("baseline", &[1.0, 2.0, 3.0])]);
+
+        cd.data[1].emphasis = Some(0.3);
+
+        let rd = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(document.matches(r#"<g class="box-plot""#).count(), 2);
+        assert_eq!(
+            document
+                .matches(r#"<g class="box-plot" opacity="0.3">"#)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn density_radii_test() {
+        let dense = density_radii(&[100.0, 100.5, 101.0, 101.5], 2.0);
+        let sparse = density_radii(&[100.0, 150.0, 200.0, 250.0], 2.0);
+
+        assert_eq!(sparse, vec![2.0; 4]);
+        assert!(dense.iter().all(|r| *r < 2.0));
+        assert!(dense.iter().sum::<f64>() < sparse.iter().sum::<f64>());
+    }
+
+    #[test]
+    fn color_map_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("mapped", &[1.0, 2.0, 3.0]), ("unmapped", &[1.0, 2.0, 3.0])]);
+        let opts = RenderOptions {
+            color_map: HashMap::from([("mapped".to_owned(), "rgb(255,0,0)".to_owned())]),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(
+            document
+                .matches(r#"<g class="box-plot" style="stroke:rgb(255,0,0);">"#)
+                .count(),
+            1
+        );
+        assert_eq!(document.matches(r#"<g class="box-plot">"#).count(), 1);
+    }
+
+    #[test]
+    fn stable_colors_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let opts = RenderOptions {
+            stable_colors: true,
+            ..Default::default()
+        };
+        let values: &[f64] = &[1.0, 2.0, 3.0];
+        let render = |cd: &ChartData| {
+            let rd = tool.process_chart_data(cd, &opts).unwrap();
+
+            tool.render_chart(&rd).unwrap().to_string()
+        };
+        let style = format!(r#"style="stroke:{};""#, stable_color("beta"));
+        let first = render(&chart_data(&[("alpha", values), ("beta", values)]));
+        let second = render(&chart_data(&[
+            ("beta", values),
+            ("gamma", values),
+            ("delta", values),
+        ]));
+
+        assert!(first.contains(&style));
+        assert!(second.contains(&style));
+        assert_eq!(stable_color("beta"), stable_color("beta"));
+        assert_ne!(stable_color("alpha"), stable_color("beta"));
+    }
+
+    #[test]
+    fn outlier_counts_test() {
+        // a: quartiles 2 and 8, fences -7 and 17; b: quartiles 21 and 30, fences 7.5 and 43.5
+        let cd = chart_data(&[
+            (
+                "a",
+                &[-20.0, -10.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 20.0],
+            ),
+            (
+                "b",
+                &[1.0, 20.0, 21.0, 22.0, 25.0, 28.0, 29.0, 30.0, 60.0, 70.0],
+            ),
+            ("c", &[1.0, 2.0, 3.0]),
+        ]);
+
+        assert_eq!(
+            outlier_counts(&cd).unwrap(),
+            vec![
+                ("a".to_owned(), 2, 1),
+                ("b".to_owned(), 1, 2),
+                ("c".to_owned(), 0, 0)
+            ]
+        );
+        assert!(outlier_counts(&chart_data(&[("d", &[1.0])])).is_err());
+    }
+
+    #[test]
+    fn render_to_string_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = ChartData::new("Latency", "ms")
+            .with_item(ItemData::new("a", &[1.0, 2.0, 3.0]))
+            .with_item(ItemData::new("b", &[2.0, 4.0, 6.0]));
+        let svg = tool
+            .render_to_string(&cd, &RenderOptions::default())
+            .unwrap();
+        let rd = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+
+        assert_eq!(svg, tool.render_chart(&rd).unwrap().to_string());
+        assert!(svg.contains("Latency (ms)"));
+        assert_eq!(svg.matches(r#"<g class="box-plot""#).count(), 2);
+    }
+
+    #[test]
+    fn render_fragments_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[
+            ("a", &[1.0, 2.0, 3.0]),
+            ("b", &[2.0, 4.0, 6.0, 50.0]),
+            ("c", &[3.0, 6.0, 9.0]),
+        ]);
+        let rd = tool
+            .process_chart_data(
+                &cd,
+                &RenderOptions {
+                    tooltips: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let fragments = tool.render_fragments(&rd).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(fragments.len(), cd.data.len());
+
+        for item in cd.data.iter() {
+            let fragment = &fragments[&item.key];
+
+            assert!(fragment.starts_with(r#"<g class="box-plot""#));
+            assert_eq!(fragment.matches("<g ").count(), 1);
+            assert_eq!(fragment.matches(r#"class="median-line""#).count(), 1);
+            assert!(fragment.contains(&format!("<title>{}: median", item.key)));
+            assert!(document.contains(fragment.as_str()));
+        }
+    }
+
+    #[test]
+    fn chart_renderer_test() {
+        struct CategoryCounter;
+
+        impl ChartRenderer for CategoryCounter {
+            type Output = usize;
+
+            fn render(&self, rd: &RenderData) -> Result<usize, Box<dyn Error>> {
+                Ok(rd.items().len())
+            }
+        }
+
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[
+            ("a", &[1.0, 2.0, 3.0]),
+            ("b", &[2.0, 4.0, 6.0]),
+            ("c", &[3.0, 6.0, 9.0]),
+        ]);
+        let opts = RenderOptions::default();
+
+        assert_eq!(tool.render_with(&CategoryCounter, &cd, &opts).unwrap(), 3);
+        assert_eq!(
+            tool.render_with(&tool, &cd, &opts).unwrap().to_string(),
+            tool.render_to_string(&cd, &opts).unwrap()
+        );
+        assert!(tool
+            .render_with(&TextRenderer, &cd, &opts)
+            .unwrap()
+            .starts_with("Key"));
+    }
+
+    #[test]
+    fn responsive_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0])]);
+        let opts = RenderOptions {
+            responsive: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let svg_tag = &document[..document.find('>').unwrap()];
+
+        assert!(svg_tag.contains(r#"viewBox="0 0 220 520""#));
+        assert!(!svg_tag.contains("width="));
+        assert!(!svg_tag.contains("height="));
+
+        let grid = tool
+            .render_grid(&[cd.clone(), cd], 2, &opts)
+            .unwrap()
+            .to_string();
+        let svg_tag = &grid[..grid.find('>').unwrap()];
+
+        assert!(svg_tag.contains(r#"viewBox="0 0 440 520""#));
+        assert!(!svg_tag.contains("width="));
+        assert_eq!(grid.matches(r#"<svg height="520""#).count(), 2);
+        assert_eq!(grid.matches(r#" width="220""#).count(), 2);
+    }
+
+    #[test]
+    fn root_attributes_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0])]);
+        let opts = RenderOptions {
+            root_class: Some("sales-chart".to_owned()),
+            preserve_aspect_ratio: Some("xMinYMin slice".to_owned()),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let charts = [cd.clone(), cd.clone()];
+
+        // The same root attributes for a chart alone, in a grid or in a stack
+        for document in [
+            tool.render_chart(&rd).unwrap(),
+            tool.render_grid(&charts, 2, &opts).unwrap(),
+            tool.render_stack(&charts, &opts).unwrap(),
+        ] {
+            let document = document.to_string();
+            let svg_tag = &document[..document.find('>').unwrap()];
+
+            assert!(svg_tag.contains(r#"class="sales-chart""#));
+            assert!(svg_tag.contains(r#"preserveAspectRatio="xMinYMin slice""#));
+        }
+
+        let rd = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let svg_tag = &document[..document.find('>').unwrap()];
+
+        assert!(!svg_tag.contains("class="));
+        assert!(!svg_tag.contains("preserveAspectRatio="));
+    }
+
+    #[test]
+    fn scale_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0])]);
+        let rd = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert!(document.contains(r#"height="520""#));
+        assert!(document.contains(r#"viewBox="0 0 220 520" width="220""#));
+
+        let opts = RenderOptions {
+            scale: Some(2.0),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert!(document.contains(r#"height="1040""#));
+        assert!(document.contains(r#"viewBox="0 0 220 520" width="440""#));
+
+        for scale in [0.0, -1.0, f64::INFINITY, f64::NAN] {
+            let opts = RenderOptions {
+                scale: Some(scale),
+                ..Default::default()
+            };
+
+            assert!(tool.process_chart_data(&cd, &opts).is_err());
+        }
+
+        assert!(Cli::try_parse_from(["", "--scale", "0"]).is_err());
+        assert!(Cli::try_parse_from(["", "--scale=-1"]).is_err());
+        assert_eq!(
+            Cli::try_parse_from(["", "--scale", "1.5"]).unwrap().scale,
+            Some(1.5)
+        );
+    }
+
+    #[test]
+    fn render_grid_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let charts: Vec<ChartData> = ["NW", "NE", "SW", "SE"]
+            .iter()
+            .map(|title| ChartData {
+                title: title.to_string(),
+                ..chart_data(&[("a", &[1.0, 2.0, 3.0]), ("b", &[2.0, 3.0, 4.0])])
+            })
+            .collect();
+        let document = tool
+            .render_grid(&charts, 2, &RenderOptions::default())
+            .unwrap()
+            .to_string();
+
+        assert_eq!(document.matches(r#"class="title""#).count(), 4);
+        assert!(document.starts_with(r#"<svg height="1040" style="background-color: white;" viewBox="0 0 560 1040" width="560""#));
+
+        for (title, x, y) in [
+            ("NW", 0, 0),
+            ("NE", 280, 0),
+            ("SW", 0, 520),
+            ("SE", 280, 520),
+        ] {
+            let cell = document
+                .find(&format!(
+                    r#"width="280" x="{}" xmlns="http://www.w3.org/2000/svg" y="{}""#,
+                    x, y
+                ))
+                .unwrap();
+            let title_index = document.find(&format!(">\n{} (ms)\n<", title)).unwrap();
+
+            assert!(cell < title_index);
+            assert!(document[cell..title_index].matches("<svg").count() == 0);
+        }
+    }
+
+    #[test]
+    fn render_stack_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let charts: Vec<ChartData> = ["CPU", "Memory", "Disk"]
+            .iter()
+            .map(|title| ChartData {
+                title: title.to_string(),
+                ..chart_data(&[("a", &[1.0, 2.0, 3.0]), ("b", &[2.0, 3.0, 4.0])])
+            })
+            .collect();
+        let document = tool
+            .render_stack(&charts, &RenderOptions::default())
+            .unwrap()
+            .to_string();
+
+        assert_eq!(document.matches(r#"class="labels""#).count(), 1);
+        assert_eq!(document.matches(r#"class="axis""#).count(), 3);
+        assert!(document.starts_with(r#"<svg height="1400" style="background-color: white;" viewBox="0 0 280 1400" width="280""#));
+
+        for (title, y) in [("CPU", 0), ("Memory", 440), ("Disk", 880)] {
+            let panel = document
+                .find(&format!(
+                    r#"width="280" x="0" xmlns="http://www.w3.org/2000/svg" y="{}""#,
+                    y
+                ))
+                .unwrap();
+            let title_index = document.find(&format!(">\n{} (ms)\n<", title)).unwrap();
+
+            assert!(panel < title_index);
+        }
+
+        // The only X labels belong to the bottom chart
+        assert!(document.find(r#"class="labels""#).unwrap() > document.rfind("<svg").unwrap());
+        assert!(tool
+            .render_stack(
+                &[
+                    charts[0].clone(),
+                    chart_data(&[("a", &[1.0, 2.0, 3.0]), ("c", &[2.0, 3.0, 4.0])])
+                ],
+                &RenderOptions::default()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn shared_y_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let charts = vec![
+            chart_data(&[("a", &[1.0, 2.0, 3.0])]),
+            chart_data(&[("a", &[10.0, 50.0, 90.0])]),
+        ];
+        let y_labels = |document: &str| -> Vec<String> {
+            document
+                .split(r#"<g class="labels y-labels">"#)
+                .skip(1)
+                .map(|s| s[..s.find("</g>").unwrap()].to_owned())
+                .collect()
+        };
+        let independent = tool
+            .render_grid(&charts, 2, &RenderOptions::default())
+            .unwrap()
+            .to_string();
+        let labels = y_labels(&independent);
+
+        assert_eq!(labels.len(), 2);
+        assert_ne!(labels[0], labels[1]);
+
+        let opts = RenderOptions {
+            shared_y: true,
+            ..Default::default()
+        };
+        let shared = tool.render_grid(&charts, 2, &opts).unwrap().to_string();
+        let labels = y_labels(&shared);
+
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[0], labels[1]);
+        assert!(labels[0].contains(">\n0\n<") && labels[0].contains(">\n90\n<"));
+    }
+
+    #[test]
+    fn locale_labels_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1000.5, 1001.0, 1001.5])]);
+        let opts = RenderOptions {
+            number_format: NumberFormat::for_locale("de").unwrap(),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(rd.y_axis_decimal_places, 2);
+        assert!(document.contains(">\n1.000,50\n<"));
+        assert!(document.contains(">\n1.001,50\n<"));
+        assert!(!document.contains(">\n1000.50\n<"));
+    }
+
+    #[test]
+    fn render_data_new_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let items = vec![
+            BoxPlotItem::new("a", Quartile::new(&[1.0, 2.0, 3.0, 4.0]).unwrap()),
+            BoxPlotItem::new("b", Quartile::new(&[2.0, 3.0, 4.0, 5.0]).unwrap())
+                .with_color("red")
+                .with_opacity(2.0),
+        ];
+        let rd = RenderData::new("Custom", "ms", items)
+            .unwrap()
+            .with_y_axis_range((-10.0, 10.0))
+            .unwrap()
+            .with_box_plot_width(100.0)
+            .with_gutter(Gutter {
+                left: 50.0,
+                ..Default::default()
+            });
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(rd.size(), (330.0, 520.0));
+        assert_eq!(rd.items()[1].opacity(), Some(1.0));
+        assert_eq!(rd.y_axis_range(), (-10.0, 10.0));
+        assert!(document.contains(r#"width="330""#));
+        assert!(document.contains(r#"style="stroke:red;""#));
+        assert!(document.contains("Custom (ms)"));
+        assert!(RenderData::new("Empty", "", vec![]).is_err());
+        assert!(rd
+            .with_y_axis_intervals(0)
+            .with_y_axis_range((0.0, 10.0))
+            .is_err());
+    }
+
+    #[test]
+    fn render_data_defaults_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0, 4.0])]);
+        let processed = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+        let constructed = RenderData::new(
+            "Test",
+            "ms",
+            vec![BoxPlotItem::new(
+                "a",
+                Quartile::new(&[1.0, 2.0, 3.0, 4.0]).unwrap(),
+            )],
+        )
+        .unwrap();
+
+        assert_eq!(constructed.y_axis_height(), processed.y_axis_height());
+        assert_eq!(constructed.y_axis_range(), processed.y_axis_range());
+        assert_eq!(constructed.box_plot_width(), processed.box_plot_width());
+        assert_eq!(constructed.outlier_radius(), processed.outlier_radius());
+        assert_eq!(
+            constructed.max_decimal_places(),
+            processed.max_decimal_places()
+        );
+        assert_eq!(constructed.gutter(), processed.gutter());
+        assert_eq!(constructed.whisker_gap(), processed.whisker_gap());
+        assert_eq!(constructed.scale(), processed.scale());
+        assert_eq!(constructed.background(), processed.background());
+        assert_eq!(constructed.styles(), processed.styles());
+    }
+
+    #[test]
+    fn max_width_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let keys: Vec<String> = (0..100).map(|i| format!("k{}", i)).collect();
+        let values = [1.0, 2.0, 3.0];
+        let items: Vec<(&str, &[f64])> = keys.iter().map(|k| (k.as_str(), &values[..])).collect();
+        let cd = chart_data(&items);
+        let opts = RenderOptions {
+            max_width: Some(800.0),
+            scale: Some(2.0),
+            ..Default::default()
+        };
+        let narrowed = tool.process_chart_data(&cd, &opts).unwrap();
+
+        assert!(narrowed.size().0 * narrowed.scale() <= 800.0);
+        assert_eq!(narrowed.items().len(), 100);
+
+        let opts = RenderOptions {
+            downsample: true,
+            ..opts
+        };
+        let sampled = tool.process_chart_data(&cd, &opts).unwrap();
+
+        assert!(sampled.size().0 * sampled.scale() <= 800.0);
+        assert_eq!(sampled.box_plot_width(), 60.0);
+        assert_eq!(sampled.items().len(), 4);
+        assert_eq!(sampled.items()[1].key(), "k25");
+
+        let opts = RenderOptions {
+            max_width: Some(100.0),
+            ..opts
+        };
+
+        assert!(tool.process_chart_data(&cd, &opts).is_err());
+    }
+
+    #[test]
+    fn min_width_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0])]);
+        let opts = RenderOptions {
+            min_width: Some(400.0),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let layout = rd.layout();
+
+        assert_eq!(rd.size().0, 400.0);
+        assert_eq!(layout.items[0].x, 200.0);
+        assert_eq!(rd.gutter().left, rd.gutter().right);
+
+        let wide = chart_data(&[
+            ("a", &[1.0, 2.0, 3.0]),
+            ("b", &[1.0, 2.0, 3.0]),
+            ("c", &[1.0, 2.0, 3.0]),
+            ("d", &[1.0, 2.0, 3.0]),
+        ]);
+
+        assert_eq!(
+            tool.process_chart_data(&wide, &opts).unwrap().gutter(),
+            Gutter::default()
+        );
+        assert!(tool
+            .process_chart_data(
+                &cd,
+                &RenderOptions {
+                    max_width: Some(300.0),
+                    ..opts
+                }
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn label_outliers_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[(
+            "a",
+            &[
+                5.0, 5.1, 48.0, 52.0, 57.0, 61.0, 64.0, 72.0, 76.0, 77.0, 81.0, 85.0, 160.0,
+            ],
+        )]);
+        let opts = RenderOptions {
+            label_outliers: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(document.matches(r#"class="outliers""#).count(), 3);
+        assert_eq!(document.matches(r#"class="outlier-labels""#).count(), 3);
+        assert!(document.contains(">\n160\n<"));
+        assert!(document.contains(">\n5.1\n<"));
+        assert!(document.contains(r#"text-anchor="end""#));
+        assert_eq!(
+            stagger_offsets(&[10.0, 12.0, 30.0, 14.0], 6.0),
+            vec![0, 1, 0, 2]
+        );
+    }
+
+    #[test]
+    fn no_outliers_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[(
+            "a",
+            &[
+                5.0, 48.0, 52.0, 57.0, 61.0, 64.0, 72.0, 76.0, 77.0, 81.0, 85.0, 88.0, 160.0,
+            ],
+        )]);
+        let opts = RenderOptions {
+            no_outliers: true,
+            label_outliers: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert!(rd.layout().items[0].outliers.is_empty());
+        assert!(!document.contains(r#"class="outliers""#));
+        assert!(!document.contains(r#"class="outlier-labels""#));
+        assert_eq!(rd.y_axis_range(), (0.0, 200.0));
+
+        let opts = RenderOptions {
+            autorange_exclude_outliers: true,
+            ..opts
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+
+        // The whiskers run from 48 to 88
+        assert_eq!(rd.y_axis_range(), (45.0, 90.0));
+    }
+
+    #[test]
+    fn whisker_endpoints_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let mut cd = chart_data(&[("a", &[10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0])]);
+        let opts = RenderOptions::default();
+        let computed = tool.process_chart_data(&cd, &opts).unwrap().layout();
+
+        cd.data[0].whisker_low = Some(25.0);
+        cd.data[0].whisker_high = Some(55.0);
+
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let whiskers = &rd.layout().items[0].whiskers;
+
+        // Y axis runs from 10 at 440 to 70 at 40, so 20 pixels per 3 units
+        assert_eq!(rd.y_axis_range(), (10.0, 70.0));
+        assert_eq!(computed.items[0].whiskers.bottom, 440.0);
+        assert_eq!(computed.items[0].whiskers.top, 40.0);
+        assert_eq!(rd.items()[0].whisker_low(), 25.0);
+        assert!((whiskers.bottom - 340.0).abs() < 1e-9);
+        assert!((whiskers.top - 140.0).abs() < 1e-9);
+
+        // Endpoints beyond the values extend the Y axis
+        cd.data[0].whisker_low = Some(0.0);
+        cd.data[0].whisker_high = Some(100.0);
+
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+
+        assert_eq!(rd.y_axis_range(), (0.0, 100.0));
+    }
+
+    #[test]
+    fn validate_test() {
+        let mut cd = chart_data(&[
+            ("a", &[1.0, 2.0, 3.0]),
+            ("b", &[1.0, f64::NAN, 3.0, f64::INFINITY]),
+            ("a", &[1.0]),
+        ]);
+
+        cd.title = " ".to_owned();
+
+        assert_eq!(
+            cd.validate().unwrap_err(),
+            vec![
+                ValidationError::EmptyTitle,
+                ValidationError::NonFiniteValue {
+                    key: "b".to_owned(),
+                    index: 1
+                },
+                ValidationError::NonFiniteValue {
+                    key: "b".to_owned(),
+                    index: 3
+                },
+                ValidationError::DuplicateKey("a".to_owned()),
+                ValidationError::TooFewValues {
+                    key: "a".to_owned(),
+                    count: 1
+                },
+            ]
+        );
+        assert_eq!(
+            chart_data(&[]).validate().unwrap_err(),
+            vec![ValidationError::NoItems]
+        );
+        assert!(chart_data(&[("a", &[1.0, 2.0, 3.0])]).validate().is_ok());
+    }
+
+    #[test]
+    fn render_to_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let mut cd = chart_data(&[("a", &[1.0, 2.0, 3.0, 4.0, 5.0])]);
+        let opts = RenderOptions::default();
+        let mut svg = vec![];
+        let mut html = vec![];
+
+        cd.title = "Latency <p99>".to_owned();
+        tool.render_to(&mut svg, &cd, OutputFormat::Svg, &opts)
+            .unwrap();
+        tool.render_to(&mut html, &cd, OutputFormat::Html, &opts)
+            .unwrap();
+
+        let svg = String::from_utf8(svg).unwrap();
+        let html = String::from_utf8(html).unwrap();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(html.starts_with("<!DOCTYPE html>\n<html>"));
+        assert!(html.contains("<title>Latency &lt;p99&gt;</title>"));
+        assert!(html.contains(&svg));
+
+        #[cfg(feature = "png")]
+        {
+            let mut png = vec![];
+
+            tool.render_to(&mut png, &cd, OutputFormat::Png, &opts)
+                .unwrap();
+
+            assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        }
+
+        #[cfg(feature = "pdf")]
+        {
+            let mut pdf = vec![];
+
+            tool.render_to(&mut pdf, &cd, OutputFormat::Pdf, &opts)
+                .unwrap();
+
+            assert!(pdf.starts_with(b"%PDF-"));
+            assert!(pdf.trim_ascii_end().ends_with(b"%%EOF"));
+        }
+    }
+
+    #[test]
+    fn text_output_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[
+            ("alpha", &[1.0, 2.0, 3.0, 4.0, 5.0]),
+            (
+                "b",
+                &[48.0, 52.0, 57.0, 64.0, 72.0, 76.0, 77.0, 81.0, 85.0, 160.0],
+            ),
+        ]);
+        let mut text = vec![];
+
+        tool.render_to(
+            &mut text,
+            &cd,
+            OutputFormat::Text,
+            &RenderOptions::default(),
+        )
+        .unwrap();
+
+        let text = String::from_utf8(text).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0],
+            "Key    Min   Q1  Median   Q3  Max  Lower outliers  Upper outliers"
+        );
+        assert!(lines[1].starts_with("-----  ---  "));
+        assert_eq!(
+            lines[2],
+            "alpha    1  1.5       3  4.5    5               0               0"
+        );
+        assert!(lines[3].starts_with("b       48   57      74   81  160"));
+        assert!(lines[3].ends_with("  160               0               1"));
+    }
+
+    #[test]
+    fn data_uri_test() {
+        use base64::Engine;
+
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0, 4.0, 5.0])]);
+        let mut uri = vec![];
+
+        tool.render_to(
+            &mut uri,
+            &cd,
+            OutputFormat::DataUri,
+            &RenderOptions::default(),
+        )
+        .unwrap();
+
+        let uri = String::from_utf8(uri).unwrap();
+        let svg = base64::engine::general_purpose::STANDARD
+            .decode(uri.strip_prefix("data:image/svg+xml;base64,").unwrap())
+            .unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains(&format!("{} (ms)", cd.title)));
+    }
+
+    #[test]
+    fn write_svg_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0])]);
+        let rd = tool
+            .process_chart_data(
+                &cd,
+                &RenderOptions {
+                    tooltips: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let mut buffer: Vec<u8> = vec![];
+
+        BoxPlotChartTool::write_svg(&mut buffer, &tool.render_chart(&rd).unwrap()).unwrap();
+
+        let content = String::from_utf8(buffer).unwrap();
+        let tags: Vec<String> = svg::read(&content)
+            .unwrap()
+            .filter_map(|event| match event {
+                svg::parser::Event::Tag(name, _, _) => Some(name.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(tags.first().unwrap(), "svg");
+        assert_eq!(tags.last().unwrap(), "svg");
+        assert!(tags.iter().any(|tag| tag == "title"));
+    }
+
+    #[test]
+    fn title_spacing_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0])]);
+        let spaced = |spacing: f64| {
+            let rd = tool
+                .process_chart_data(
+                    &cd,
+                    &RenderOptions {
+                        title_spacing: Some(spacing),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            let document = tool.render_chart(&rd).unwrap().to_string();
+
+            (rd.layout().plot.y, document)
+        };
+        let (near_plot_y, near) = spaced(20.0);
+        let (far_plot_y, far) = spaced(60.0);
+        let title = format!(r#"<text class="title" x="110" y="{}">"#, TITLE_BASELINE);
+
+        assert_eq!(near_plot_y, TITLE_BASELINE + 20.0);
+        assert_eq!(far_plot_y, near_plot_y + 40.0);
+        assert!(near.contains(&title));
+        assert!(far.contains(&title));
+        assert!(tool
+            .process_chart_data(
+                &cd,
+                &RenderOptions {
+                    title_spacing: Some(-1.0),
+                    ..Default::default()
+                }
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn wrap_title_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = ChartData {
+            title: "Response times of the checkout service measured at every edge location"
+                .to_owned(),
+            ..chart_data(&[("a", &[1.0, 2.0, 3.0]), ("b", &[2.0, 3.0, 4.0])])
+        };
+        let plain = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+        let wrapped = tool
+            .process_chart_data(
+                &cd,
+                &RenderOptions {
+                    wrap_title: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let lines = wrapped.title_lines();
+
+        assert_eq!(plain.title_lines().len(), 1);
+        assert!(lines.len() > 1);
+        assert_eq!(lines.join(" "), plain.title_lines()[0]);
+        assert_eq!(
+            wrapped.gutter().top,
+            plain.gutter().top + (lines.len() - 1) as f64 * TITLE_LINE_HEIGHT
+        );
+        assert_eq!(
+            wrapped.size().1 - plain.size().1,
+            wrapped.gutter().top - plain.gutter().top
+        );
+
+        let document = tool.render_chart(&wrapped).unwrap().to_string();
+
+        assert_eq!(document.matches("<tspan").count(), lines.len());
+        assert_eq!(wrap_text("a bb ccc", 4), vec!["a bb", "ccc"]);
+        assert_eq!(wrap_text("abcdef g", 4), vec!["abcdef", "g"]);
+    }
+
+    #[test]
+    fn merge_duplicates_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[
+            ("a", &[1.0, 2.0, 3.0]),
+            ("b", &[5.0, 6.0, 7.0]),
+            ("a", &[4.0, 5.0, 6.0]),
+        ]);
+        let opts = RenderOptions {
+            merge_duplicates: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let unmerged = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+        let keys: Vec<&str> = rd.items().iter().map(|item| item.key()).collect();
+
+        assert_eq!(keys, vec!["a", "b"]);
+        assert_eq!(unmerged.items().len(), 3);
+        assert_eq!(
+            rd.items()[0].quartile(),
+            &Quartile::new(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn label_iqr_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[
+            (
+                "a",
+                &[48.0, 52.0, 57.0, 64.0, 72.0, 76.0, 77.0, 81.0, 85.0, 88.0],
+            ),
+            ("b", &[1.0, 2.5, 3.0, 4.0]),
+        ]);
+        let opts = RenderOptions {
+            label_iqr: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(rd.items()[0].quartile().iqr(), 24.0);
+        assert_eq!(rd.items()[1].quartile().iqr(), 1.75);
+        assert_eq!(document.matches(r#"class="iqr-labels""#).count(), 2);
+        assert!(document.contains(">\n24 ms\n<"));
+        assert!(document.contains(">\n1.75 ms\n<"));
+    }
+
+    #[test]
+    fn lollipop_style_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0, 4.0]), ("b", &[2.0, 4.0, 6.0])]);
+        let opts = RenderOptions {
+            style: PlotStyle::Lollipop,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(document.matches("<line ").count(), 2);
+        assert_eq!(document.matches(r#"<circle class="median""#).count(), 2);
+        assert!(!document.contains("<path"));
+    }
+
+    #[test]
+    fn median_style_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0])]);
+        let opts = RenderOptions {
+            median_style: MedianStyle::Dot,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let item = &rd.layout().items[0];
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert!(document.contains(&format!(
+            r#"<circle class="median" cx="{}" cy="{}""#,
+            item.x, item.median_y
+        )));
+        assert!(!document.contains(r#"class="median-line""#));
+
+        let opts = RenderOptions {
+            median_style: MedianStyle::DotNotch,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(document.matches(r#"<circle class="median""#).count(), 1);
+        assert!(document.contains(&format!(
+            "L{},{}",
+            item.x - item.box_rect.width / 4.0,
+            item.median_y
+        )));
+
+        let rd = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert!(!document.contains(r#"<circle class="median""#));
+        assert!(document.contains(r#"<path class="median-line""#));
+    }
+
+    #[test]
+    fn summary_notch_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let summary = || Quartile::from_summary(1.0, 3.0, 5.0, 7.0, 9.0).unwrap();
+        let render = |quartile: Quartile| {
+            let rd = RenderData::new("Test", "ms", vec![BoxPlotItem::new("a", quartile)])
+                .unwrap()
+                .with_median_style(MedianStyle::Notch);
+            let item = rd.layout().items[0].clone();
+            let document = tool.render_chart(&rd).unwrap().to_string();
+
+            document.contains(&format!(
+                "L{},{}",
+                item.x - item.box_rect.width / 4.0,
+                item.median_y
+            ))
+        };
+
+        // Five summary values say nothing of the size of the sample behind them
+        assert!(!render(summary()));
+        assert!(render(summary().with_sample_size(1000)));
+    }
+
+    #[test]
+    fn summary_statistics_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let rd = || {
+            RenderData::new(
+                "Test",
+                "ms",
+                vec![
+                    BoxPlotItem::new("a", Quartile::new(&[1.0, 2.0, 3.0]).unwrap()),
+                    BoxPlotItem::new(
+                        "b",
+                        Quartile::from_summary(1.0, 3.0, 5.0, 7.0, 9.0).unwrap(),
+                    ),
+                ],
+            )
+            .unwrap()
+        };
+        let summary = rd().summary();
+
+        // Nothing is made up from the five summary values
+        assert_eq!(summary.global, None);
+        assert_eq!(summary.categories[0].mean, Some(2.0));
+        assert_eq!(summary.categories[0].count, Some(3));
+        assert_eq!(summary.categories[1].mean, None);
+        assert_eq!(summary.categories[1].count, None);
+        assert_eq!(summary.categories[1].median, 5.0);
+        assert!(tool.render_chart(&rd()).is_ok());
+        assert!(rd()
+            .layout()
+            .items
+            .iter()
+            .all(|item| item.error_bar.is_none()));
+        assert!(tool.render_chart(&rd().with_error_bars(true)).is_err());
+        assert!(tool.render_chart(&rd().with_embed_data(true)).is_err());
+    }
+
+    #[test]
+    fn zero_spread_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[5.0, 5.0, 5.0, 5.0]), ("b", &[1.0, 2.0, 3.0])]);
+        let rd = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+        let item = &rd.layout().items[0];
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let group = &document[document.find(r#"<g class="box-plot zero-spread""#).unwrap()..];
+        let group = &group[..group.find("</g>").unwrap()];
+
+        assert_eq!(item.box_rect.height, 0.0);
+        assert!(document.contains(".zero-spread .median-line{stroke-width:2;}"));
+        assert_eq!(document.matches("zero-spread\"").count(), 1);
+        assert!(group.contains(&format!(
+            r#"<path class="median-line" d="M{},{} l{},0""#,
+            item.box_rect.x, item.median_y, item.box_rect.width
+        )));
+        assert!(!group.contains("whiskers"));
+
+        let opts = RenderOptions {
+            zero_spread: ZeroSpread::Box,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let group = &document[document.find(r#"<g class="box-plot zero-spread""#).unwrap()..];
+        let group = &group[..group.find("</g>").unwrap()];
+
+        assert!(group.contains(&format!("l0,{}", 2.0 * ZERO_SPREAD_HALF_HEIGHT)));
+        assert!(group.contains(r#"<path class="median-line""#));
+    }
+
+    #[test]
+    fn constant_category_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+
+        for values in [[5.0, 5.0, 5.0], [0.0, 0.0, 0.0], [-2.5, -2.5, -2.5]] {
+            let cd = chart_data(&[("a", &values)]);
+
+            for zero_spread in [ZeroSpread::Line, ZeroSpread::Box] {
+                let opts = RenderOptions {
+                    zero_spread,
+                    ..Default::default()
+                };
+                let rd = tool.process_chart_data(&cd, &opts).unwrap();
+                let document = tool.render_chart(&rd).unwrap().to_string();
+                let (min, max) = rd.y_axis_range();
+
+                assert!(min < values[0] && values[0] < max);
+                assert!(!document.contains("NaN"));
+                assert!(!document.contains("inf"));
+            }
+        }
+
+        let rd = RenderData::new(
+            "Test",
+            "ms",
+            vec![BoxPlotItem::new(
+                "a",
+                Quartile::new(&[5.0, 5.0, 5.0]).unwrap(),
+            )],
+        )
+        .unwrap();
+
+        assert!(!tool.render_chart(&rd).unwrap().to_string().contains("NaN"));
+        assert!(rd.with_y_axis_range((5.0, 5.0)).is_err());
+    }
+
+    #[test]
+    fn embed_data_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[3.0, 1.5, 2.0, 40.0]), ("b", &[-2.0, 4.0, 6.0])]);
+        let opts = RenderOptions {
+            embed_data: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let embedded: Vec<Vec<f64>> = document
+            .match_indices(r#"data-values=""#)
+            .map(|(i, attr)| {
+                let start = i + attr.len();
+                let end = start + document[start..].find('"').unwrap();
+
+                json5::from_str(&document[start..end]).unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            embedded,
+            vec![vec![1.5, 2.0, 3.0, 40.0], vec![-2.0, 4.0, 6.0]]
+        );
+
+        let rd = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+
+        assert!(!tool
+            .render_chart(&rd)
+            .unwrap()
+            .to_string()
+            .contains("data-values"));
+    }
+
+    #[test]
+    fn strip_style_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[
+            ("a", &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 100.0]),
+            ("b", &[2.0, 4.0, 6.0]),
+            ("c", &[3.0, 5.0, 7.0]),
+        ]);
+        let opts = RenderOptions {
+            style: PlotStyle::Strip,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(rd.items()[0].quartile().upper_outliers(), vec![100.0]);
+        assert_eq!(document.matches("<circle ").count(), 3);
+        assert_eq!(document.matches(r#"<circle class="median""#).count(), 3);
+        assert!(!document.contains("<line "));
+        assert!(!document.contains("<path"));
+    }
+
+    #[test]
+    fn value_scale_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1024.0, 2048.0, 3072.0, 4096.0, 5120.0])]);
+        let opts = RenderOptions {
+            value_scale: Some(parse_ratio("1/1024").unwrap()),
+            value_units: Some("KB".to_owned()),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let quartile = rd.items()[0].quartile();
+
+        assert_eq!(quartile.min_value(), 1.0);
+        assert_eq!(quartile.median(), 3.0);
+        assert_eq!(quartile.max_value(), 5.0);
+        assert_eq!(rd.y_axis_range(), (1.0, 5.0));
+        assert_eq!(rd.units(), "KB");
+
+        let opts = RenderOptions {
+            value_offset: Some(-1.0),
+            ..opts
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+
+        assert_eq!(rd.y_axis_range(), (0.0, 4.0));
+        assert!(parse_ratio("1/0").is_err());
+        assert_eq!(parse_ratio("0.5"), Ok(0.5));
+    }
+
+    #[test]
+    fn max_decimal_places_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[0.0000012, 0.00000125, 0.0000013])]);
+        let rd = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+        let labels: Vec<String> = rd.layout().ticks.into_iter().map(|t| t.label).collect();
+
+        assert_eq!(rd.y_axis_decimal_places(), 9);
+        // Mantissas keep the 9 decimal places of the tick interval
+        assert!(labels
+            .iter()
+            .all(|label| label.len() == 8 && label.starts_with("1.") && label.ends_with("e-6")));
+
+        let rd = tool
+            .process_chart_data(
+                &cd,
+                &RenderOptions {
+                    max_decimal_places: Some(9),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let label = &rd.layout().ticks[0].label;
+
+        assert!(label.len() == 11 && label.starts_with("0.000001"));
+    }
+
+    #[test]
+    fn whisker_gap_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0])]);
+        let whiskers = |opts: &RenderOptions| {
+            let rd = tool.process_chart_data(&cd, opts).unwrap();
+            let document = tool.render_chart(&rd).unwrap().to_string();
+            let start = document.find(r#"<path class="whiskers" d=""#).unwrap() + 26;
+            let end = start + document[start..].find('"').unwrap();
+
+            (
+                rd.layout().items[0].box_rect.clone(),
+                document[start..end].to_owned(),
+            )
+        };
+        let (box_rect, touching) = whiskers(&RenderOptions::default());
+
+        assert!(touching.contains(&format!(
+            "L{},{}",
+            box_rect.x + box_rect.width / 2.0,
+            box_rect.y
+        )));
+
+        let (box_rect, gapped) = whiskers(&RenderOptions {
+            whisker_gap: Some(3.0),
+            ..Default::default()
+        });
+        let x = box_rect.x + box_rect.width / 2.0;
+
+        assert!(gapped.contains(&format!("L{},{}", x, box_rect.y - 3.0)));
+        assert!(gapped.contains(&format!("M{},{}", x, box_rect.y + box_rect.height + 3.0)));
+        assert_ne!(touching, gapped);
+        assert!(tool
+            .process_chart_data(
+                &cd,
+                &RenderOptions {
+                    whisker_gap: Some(-1.0),
+                    ..Default::default()
+                }
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn dash_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0, 4.0, 5.0])]);
+        let opts = RenderOptions {
+            whisker_dash: Some(parse_dash("4 2").unwrap()),
+            median_dash: Some(parse_dash("2,1").unwrap()),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert!(rd
+            .styles()
+            .contains(&".whiskers{stroke-dasharray:4,2;}".to_owned()));
+        assert!(document.contains(".median-line{stroke-dasharray:2,1;}"));
+        assert!(document.contains(r#"<path class="whiskers""#));
+        assert!(document.contains(r#"<path class="median-line""#));
+        assert!(parse_dash("4,x").is_err());
+        assert!(parse_dash("").is_err());
+    }
+
+    #[test]
+    fn axis_and_label_color_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0, 4.0, 5.0])]);
+        let opts = RenderOptions {
+            axis_color: Some("rgb(0,0,255)".to_owned()),
+            label_color: Some("gray".to_owned()),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert!(document.contains(".axis{stroke:rgb(0,0,255);}"));
+        assert!(document.contains(".labels{fill:gray;}"));
+
+        // The overrides follow the theme rules so that they take precedence
+        let styles = rd.styles();
+        let position = |prefix: &str| styles.iter().position(|style| style.starts_with(prefix));
+
+        assert!(position(".axis{stroke:") > position(".axis{fill:"));
+        assert!(position(".labels{fill:gray;}") > position(".labels{fill:"));
+    }
+
+    #[test]
+    fn line_style_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0, 4.0, 5.0])]);
+        let opts = RenderOptions {
+            linejoin: Some(LineJoin::Round),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+
+        assert!(rd
+            .styles()
+            .contains(&".box-plot{stroke-linejoin:round;}".to_owned()));
+
+        let opts = RenderOptions {
+            linecap: Some(LineCap::Square),
+            ..opts
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+
+        assert!(tool
+            .render_chart(&rd)
+            .unwrap()
+            .to_string()
+            .contains(".box-plot{stroke-linejoin:round;stroke-linecap:square;}"));
+    }
+
+    #[test]
+    fn map_data_test() {
+        let cd: ChartData = json5::from_str(
+            r#"{ title: "Map", units: "ms", data: { "B": [4, 5, 6], "A": [1, 2, 3] } }"#,
+        )
+        .unwrap();
+        let items: Vec<(&str, &[f64])> = cd
+            .data
+            .iter()
+            .map(|item| (item.key.as_str(), item.values.as_slice()))
+            .collect();
+
+        assert_eq!(cd.title, "Map");
+        assert_eq!(
+            items,
+            vec![("B", &[4.0, 5.0, 6.0][..]), ("A", &[1.0, 2.0, 3.0][..])]
+        );
+        assert!(cd.data.iter().all(|item| item.emphasis.is_none()));
+        assert!(json5::from_str::<ChartData>(r#"{ title: "", units: "", data: 1 }"#).is_err());
+    }
+
+    #[test]
+    fn render_hints_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd: ChartData = json5::from_str(
+            r#"{
+                title: "Hints",
+                units: "ms",
+                data: { a: [1, 2, 3], b: [2, 3, 4] },
+                render: { outlier_radius: 4, box_plot_width: 90 },
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            cd.render,
+            RenderHints {
+                outlier_radius: Some(4.0),
+                box_plot_width: Some(90.0)
+            }
+        );
+
+        let rd = tool.process_chart_data(&cd, &Default::default()).unwrap();
+
+        assert_eq!(rd.outlier_radius(), 4.0);
+        assert_eq!(rd.box_plot_width(), 90.0);
+
+        // Options that are set take precedence over the hints
+        let opts = RenderOptions {
+            box_plot_width: Some(50.0),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+
+        assert_eq!(rd.outlier_radius(), 4.0);
+        assert_eq!(rd.box_plot_width(), 50.0);
+
+        let cd: ChartData =
+            json5::from_str(r#"{ title: "", units: "", data: { a: [1, 2, 3] } }"#).unwrap();
+
+        assert_eq!(cd.render, RenderHints::default());
+        assert_eq!(cd.pages(1).unwrap()[0].render, cd.render);
+    }
+
+    #[test]
+    fn mixed_values_test() {
+        let cd: ChartData = json5::from_str(
+            r#"{ title: "Mixed", units: "ms", data: [{ key: "a", values: [1, 2.5, "3"] }] }"#,
+        )
+        .unwrap();
+
+        assert_eq!(cd.data[0].values, vec![1.0, 2.5, 3.0]);
+
+        let cd: ChartData =
+            json5::from_str(r#"{ title: "Mixed", units: "ms", data: { a: [" 4 ", -5, 6e1] } }"#)
+                .unwrap();
+
+        assert_eq!(cd.data[0].values, vec![4.0, -5.0, 60.0]);
+
+        let error = json5::from_str::<ChartData>(
+            r#"{ title: "Mixed", units: "ms", data: [{ key: "a", values: [1, "two", 3] }] }"#,
+        )
+        .unwrap_err();
+
+        assert!(error.to_string().contains(r#"value "two" is not a number"#));
+        assert!(json5::from_str::<ChartData>(
+            r#"{ title: "Mixed", units: "ms", data: [{ key: "a", values: [1, true, 3] }] }"#,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn value_count_warning_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let many: Vec<f64> = (0..31).map(|n| n as f64).collect();
+        let opts = RenderOptions::default();
+
+        tool.process_chart_data(&chart_data(&[("a", &many[..30]), ("b", &many[..3])]), &opts)
+            .unwrap();
+
+        assert!(logger.warnings().is_empty());
+
+        tool.process_chart_data(&chart_data(&[("a", &many), ("b", &many[..3])]), &opts)
+            .unwrap();
+
+        assert_eq!(
+            logger.warnings(),
+            vec!["Category 'b' has 3 values but 'a' has 31; their box plots may not be comparable"]
+        );
+    }
+
+    #[test]
+    fn shape_rendering_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[-1.0, 2.0, 3.0]), ("b", &[1.0, 2.0, 3.0])]);
+        let opts = RenderOptions {
+            zero_line: true,
+            axis_shape_rendering: Some(ShapeRendering::CrispEdges),
+            box_shape_rendering: Some(ShapeRendering::GeometricPrecision),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let axis = document.find(r#"<polyline class="axis""#).unwrap();
+        let axis = &document[axis..axis + document[axis..].find("/>").unwrap()];
+
+        assert!(axis.ends_with(r#"shape-rendering="crispEdges""#));
+        assert_eq!(
+            document.matches(r#"shape-rendering="crispEdges""#).count(),
+            2
+        );
+        assert_eq!(
+            document
+                .matches(r#"shape-rendering="geometricPrecision""#)
+                .count(),
+            2
+        );
+
+        let plain = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+
+        assert!(!tool
+            .render_chart(&plain)
+            .unwrap()
+            .to_string()
+            .contains("shape-rendering"));
+    }
+
+    #[test]
+    fn weight_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let mut cd = chart_data(&[
+            ("a", &[1.0, 2.0, 3.0]),
+            ("b", &[1.0, 2.0, 3.0]),
+            ("c", &[1.0, 2.0, 3.0]),
+        ]);
+
+        cd.data[1].weight = Some(2.0);
+
+        let rd = tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .unwrap();
+        let layout = rd.layout();
+        let widths: Vec<f64> = layout
+            .items
+            .iter()
+            .map(|item| item.box_rect.width)
+            .collect();
+        let xs: Vec<f64> = layout.items.iter().map(|item| item.x).collect();
+
+        assert_eq!(rd.size().0, 80.0 + 3.0 * 60.0 + 80.0);
+        assert_eq!(widths, vec![15.0, 30.0, 15.0]);
+        assert_eq!(xs, vec![102.5, 170.0, 237.5]);
+        assert_eq!(
+            layout.items[1].whiskers.width,
+            2.0 * layout.items[0].whiskers.width
+        );
+
+        cd.data[1].weight = Some(0.0);
+
+        assert!(tool
+            .process_chart_data(&cd, &RenderOptions::default())
+            .is_err());
+    }
+
+    #[test]
+    fn envelope_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[
+            ("a", &[0.0, 10.0, 20.0, 30.0, 40.0]),
+            (
+                "b",
+                &[48.0, 52.0, 57.0, 64.0, 72.0, 76.0, 77.0, 81.0, 85.0, 160.0],
+            ),
+            ("c", &[1.0, 2.0, 3.0]),
+        ]);
+        let opts = RenderOptions {
+            envelope: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let layout = rd.layout();
+        let envelopes: Vec<Vec<f64>> = document
+            .match_indices(r#"<polyline class="envelope" points=""#)
+            .map(|(i, prefix)| {
+                let points = &document[i + prefix.len()..];
+
+                points[..points.find('"').unwrap()]
+                    .split(' ')
+                    .map(|n| n.parse().unwrap())
+                    .collect()
+            })
+            .collect();
 
-            box_plots.append(box_plot);
+        assert_eq!(envelopes.len(), 2);
+
+        for points in envelopes.iter() {
+            assert_eq!(points.len(), 2 * cd.data.len());
         }
 
-        let title = element::Text::new(format!("{} ({})", &rd.title, &rd.units))
-            .set("class", "title")
-            .set("x", width / 2.0)
-            .set("y", rd.gutter.top / 2.0);
+        // The upper outlier of b, rather than its whisker, is the top of the envelope
+        let outlier = &layout.items[1].outliers[0];
 
-        document.append(style);
-        document.append(axis);
-        document.append(x_axis_labels);
-        document.append(y_axis_labels);
-        document.append(box_plots);
-        document.append(title);
+        assert_eq!(envelopes[0][2..4], [outlier.cx, outlier.cy]);
+        assert_eq!(
+            envelopes[1][4..6],
+            [layout.items[2].x, layout.items[2].whiskers.bottom]
+        );
+        assert!(!tool
+            .render_chart(
+                &tool
+                    .process_chart_data(&cd, &RenderOptions::default())
+                    .unwrap()
+            )
+            .unwrap()
+            .to_string()
+            .contains("envelope\""));
+    }
 
-        Ok(document)
+    #[test]
+    fn box_guides_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[
+            ("a", &[0.0, 10.0, 20.0, 30.0, 40.0]),
+            ("b", &[1.0, 2.0, 3.0]),
+        ]);
+        let opts = RenderOptions {
+            box_guides: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let layout = rd.layout();
+        let item = &layout.items[0];
+        let box_rect = &item.box_rect;
+
+        assert_eq!(document.matches(r#"<line class="box-guides""#).count(), 6);
+
+        for y in [box_rect.y, item.median_y, box_rect.y + box_rect.height] {
+            assert!(document.contains(&format!(
+                r#"<line class="box-guides" x1="{}" x2="{}" y1="{}" y2="{}"/>"#,
+                box_rect.x - 6.0,
+                box_rect.x + box_rect.width + 6.0,
+                y,
+                y
+            )));
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn zebra_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[
+            ("a", &[1.0, 2.0, 3.0]),
+            ("b", &[2.0, 3.0, 4.0]),
+            ("c", &[3.0, 4.0, 5.0]),
+            ("d", &[4.0, 5.0, 6.0]),
+            ("e", &[5.0, 6.0, 7.0]),
+        ]);
+        let rd = tool.process_chart_data(&cd, &Default::default()).unwrap();
+
+        assert!(!tool
+            .render_chart(&rd)
+            .unwrap()
+            .to_string()
+            .contains(r#"<g class="zebra">"#));
+
+        let opts = RenderOptions {
+            zebra: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let stripes = document.find(r#"<g class="zebra">"#).unwrap();
+        let stripes = &document[stripes..stripes + document[stripes..].find("</g>").unwrap()];
+
+        assert_eq!(stripes.matches("<rect").count(), 3);
+        assert!(stripes.contains(&format!(
+            r#"<rect height="{}" width="60" x="{}" y="{}"/>"#,
+            rd.y_axis_height(),
+            rd.gutter().left + 120.0,
+            rd.gutter().top
+        )));
+        assert!(
+            document.find(r#"<g class="zebra">"#).unwrap()
+                < document.find(r#"class="box-plot""#).unwrap()
+        );
+    }
 
     #[test]
-    fn basic_test() {
-        struct TestLogger;
+    fn normalize_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[
+            ("a", &[10.0, 20.0, 30.0, 50.0]),
+            ("b", &[-1000.0, 0.0, 1000.0]),
+        ]);
+        let opts = RenderOptions {
+            normalize: Some(Normalization::PerCategory),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
 
-        impl TestLogger {
-            fn new() -> TestLogger {
-                TestLogger {}
-            }
+        for item in rd.items() {
+            assert_eq!(item.quartile().min_value(), 0.0);
+            assert_eq!(item.quartile().max_value(), 100.0);
+        }
+
+        assert_eq!(rd.items()[0].quartile().median(), 37.5);
+        assert_eq!(rd.y_axis_range(), (0.0, 100.0));
+        assert_eq!(rd.units(), "%");
+    }
+
+    #[test]
+    fn plot_aspect_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let values: &[f64] = &[1.0, 2.0, 3.0];
+        let cd = chart_data(&[("a", values), ("b", values), ("c", values)]);
+        let opts = RenderOptions {
+            plot_aspect: Some(parse_aspect("16:9").unwrap()),
+            box_plot_width: Some(50.0),
+            ..Default::default()
+        };
+        let plot = tool.process_chart_data(&cd, &opts).unwrap().layout().plot;
+
+        assert_eq!(plot.width, 150.0);
+        assert!((plot.width / plot.height - 16.0 / 9.0).abs() < 1e-9);
+        assert!(parse_aspect("16:0").is_err());
+        assert!(parse_aspect("16x9").is_err());
+    }
+
+    #[test]
+    fn order_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let values: &[f64] = &[1.0, 2.0, 3.0];
+        let cd = chart_data(&[
+            ("A", values),
+            ("B", values),
+            ("C", values),
+            ("D", values),
+            ("E", values),
+        ]);
+        let opts = RenderOptions {
+            order: Some(vec!["C".to_owned(), "A".to_owned(), "X".to_owned()]),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let keys: Vec<&str> = rd.items().iter().map(|item| item.key()).collect();
+
+        assert_eq!(keys, vec!["C", "A", "B", "D", "E"]);
+        assert_eq!(
+            logger.warnings(),
+            vec![
+                "Category 'X' in the order is not in the data",
+                "Categories 'B', 'D', 'E' are not in the order and follow those that are"
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_layout_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0])]);
+        let invalid = [
+            RenderOptions {
+                box_plot_width: Some(0.0),
+                ..Default::default()
+            },
+            RenderOptions {
+                y_axis_height: Some(-400.0),
+                ..Default::default()
+            },
+            RenderOptions {
+                gutter: Some(Gutter {
+                    left: -1.0,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ];
+
+        for opts in invalid.iter() {
+            assert!(tool.process_chart_data(&cd, opts).is_err());
         }
 
-        impl BoxPlotChartLog for TestLogger {
-            fn output(self: &Self, _args: Arguments) {}
-            fn warning(self: &Self, _args: Arguments) {}
-            fn error(self: &Self, _args: Arguments) {}
+        let items = vec![BoxPlotItem::new(
+            "a",
+            Quartile::new(&[1.0, 2.0, 3.0]).unwrap(),
+        )];
+        let rd = RenderData::new("Test", "ms", items)
+            .unwrap()
+            .with_y_axis_height(f64::NAN);
+
+        assert!(tool.render_chart(&rd).is_err());
+    }
+
+    #[test]
+    fn item_units_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let mut cd = chart_data(&[("a", &[1.0, 2.0, 3.0]), ("b", &[4.0, 5.0, 6.0])]);
+
+        cd.data[1].units = Some("s".to_owned());
+
+        let rd = tool.process_chart_data(&cd, &Default::default()).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert!(!document.contains("<title>"));
+
+        let rd = tool
+            .process_chart_data(
+                &cd,
+                &RenderOptions {
+                    tooltips: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert!(document.contains("<title>a: median 2.0 ms, quartiles 1.0 ms to 3.0 ms</title>"));
+        assert!(document.contains("<title>b: median 5.0 s, quartiles 4.0 s to 6.0 s</title>"));
+    }
+
+    #[test]
+    fn layout_json_test() {
+        let logger = TestLogger::new();
+        let mut tool = BoxPlotChartTool::new(&logger);
+        let dir = TempDir::new("layout_json");
+        let layout_path = dir.join("layout.json");
+        let args: Vec<std::ffi::OsString> = vec![
+            "".into(),
+            example().into(),
+            dir.join("chart.svg").into(),
+            "--layout-json".into(),
+            layout_path.clone().into(),
+        ];
+
+        tool.run(args).unwrap();
+
+        let json = std::fs::read_to_string(&layout_path).unwrap();
+        let layout: ChartLayout = json5::from_str(&json).unwrap();
+        let svg = std::fs::read_to_string(dir.join("chart.svg")).unwrap();
+
+        assert!(json.contains(r#""box":{"#));
+        assert!(!layout.items.is_empty());
+
+        for item in layout.items.iter() {
+            let box_rect = &item.box_rect;
+
+            assert!(svg.contains(&format!("M{},{}", box_rect.x, item.median_y)));
+            assert!(box_rect.width > 0.0);
+            assert!(item.whiskers.top <= box_rect.y);
+            assert!(box_rect.y + box_rect.height <= item.whiskers.bottom);
         }
+    }
 
+    #[test]
+    fn config_file_test() {
         let logger = TestLogger::new();
         let mut tool = BoxPlotChartTool::new(&logger);
-        let args: Vec<std::ffi::OsString> = vec!["".into(), "--help".into()];
+        let dir = TempDir::new("config_file");
+        let config_path = dir.join("config.json5");
+        let svg_path = dir.join("chart.svg");
+        let render = |tool: &mut BoxPlotChartTool, extra: &[&str]| {
+            let mut args: Vec<std::ffi::OsString> = vec![
+                "".into(),
+                example().into(),
+                svg_path.clone().into(),
+                "--config".into(),
+                config_path.clone().into(),
+            ];
+
+            args.extend(extra.iter().map(|arg| arg.into()));
+            tool.run(args)?;
+
+            Ok::<String, Box<dyn Error>>(std::fs::read_to_string(&svg_path).unwrap())
+        };
+
+        std::fs::write(
+            &config_path,
+            r#"{ theme: "dark", "label-iqr": true, "outlier-radius": 5 }"#,
+        )
+        .unwrap();
+
+        let svg = render(&mut tool, &[]).unwrap();
+
+        assert!(svg.contains(&format!("background-color: {};", Theme::Dark.background())));
+        assert!(svg.contains(r#"class="iqr-labels""#));
+        assert!(svg.contains(r#"r="5""#));
+
+        let svg = render(&mut tool, &["--theme", "light"]).unwrap();
+
+        assert!(svg.contains(&format!("background-color: {};", Theme::Light.background())));
+        assert!(svg.contains(r#"class="iqr-labels""#));
+
+        std::fs::write(&config_path, r#"{ "no-such-option": 1 }"#).unwrap();
+
+        assert!(render(&mut tool, &[]).is_err());
+
+        std::fs::write(&config_path, r#"{ minify: false }"#).unwrap();
+
+        let message = render(&mut tool, &[]).unwrap_err().to_string();
+
+        assert!(message.contains("'minify'"));
+        assert!(message.contains(&config_path.to_string_lossy().to_string()));
+    }
+
+    #[test]
+    fn summary_json_test() {
+        let logger = TestLogger::new();
+        let mut tool = BoxPlotChartTool::new(&logger);
+        let dir = TempDir::new("summary_json");
+        let summary_path = dir.join("summary.json");
+        let input_path = dir.join("input.json5");
+
+        std::fs::write(
+            &input_path,
+            r#"{ title: "Load", units: "ms", data: { a: [1, 2, 3, 4], b: [5, 6, 7, 100] } }"#,
+        )
+        .unwrap();
+
+        let args: Vec<std::ffi::OsString> = vec![
+            "".into(),
+            input_path.into(),
+            dir.join("chart.svg").into(),
+            "--summary-json".into(),
+            summary_path.clone().into(),
+            "--with-total".into(),
+        ];
+
+        tool.run(args).unwrap();
+
+        let json = std::fs::read_to_string(&summary_path).unwrap();
+        let summary: ChartSummary = json5::from_str(&json).unwrap();
+
+        assert!(json.contains(r#""global":{"#));
+        assert!(json.contains(r#""categories":["#));
+        assert_eq!(
+            summary.global,
+            Some(GlobalSummary {
+                count: 8,
+                min: 1.0,
+                median: 4.5,
+                max: 100.0
+            })
+        );
+        assert_eq!(
+            summary
+                .categories
+                .iter()
+                .map(|category| category.key.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["a", "b", "All"]
+        );
+        assert_eq!(summary.categories[0].q1, 1.5);
+        assert_eq!(summary.categories[1].median, 6.5);
+    }
+
+    #[test]
+    fn precision_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[(
+            "a",
+            &[
+                5.0, 6.0, 48.0, 52.0, 57.0, 61.0, 64.0, 72.0, 76.0, 77.0, 81.0, 85.0, 88.0,
+            ],
+        )]);
+        let opts = RenderOptions {
+            label_outliers: true,
+            y_tick_precision: Some(1),
+            value_precision: Some(3),
+            tooltips: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(rd.y_axis_decimal_places(), 0);
+        assert!(document.contains(">\n50.0\n<"));
+        assert!(!document.contains(">\n50\n<"));
+        assert!(document.contains(">\n5.000\n<"));
+        assert!(document.contains("median 64.000 ms"));
+    }
+
+    #[test]
+    fn significant_figures_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1000.0, 1234.5, 1500.0, 1750.0, 2000.0])]);
+        let opts = RenderOptions {
+            significant_figures: Some(2),
+            tooltips: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(rd.format_number(1234.5, 3), "1200");
+        assert!(document.contains("median 1500 ms, quartiles 1100 ms to 1900 ms"));
+        assert!(tool
+            .process_chart_data(
+                &cd,
+                &RenderOptions {
+                    significant_figures: Some(0),
+                    ..Default::default()
+                }
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn with_total_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0, 4.0]), ("b", &[10.0, 20.0, 30.0])]);
+        let opts = RenderOptions {
+            with_total: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let total = rd.items().last().unwrap();
+
+        assert_eq!(rd.items().len(), 3);
+        assert_eq!(total.key(), "All");
+        assert!(total.is_total());
+        assert_eq!(
+            total.quartile(),
+            &Quartile::new(&[1.0, 2.0, 3.0, 4.0, 10.0, 20.0, 30.0]).unwrap()
+        );
+        assert_eq!(document.matches(r#"class="box-plot total""#).count(), 1);
+
+        // A category with the total key leaves the total numbered
+        let cd = chart_data(&[("All", &[1.0, 2.0, 3.0]), ("b", &[10.0, 20.0, 30.0])]);
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+
+        assert_eq!(rd.items()[0].key(), "All");
+        assert_eq!(rd.items()[2].key(), "All 2");
+
+        // Only the categories kept to fit the width are in the total
+        let cd = chart_data(&[
+            ("a", &[1.0, 2.0, 3.0]),
+            ("b", &[10.0, 20.0, 30.0]),
+            ("c", &[100.0, 200.0, 300.0]),
+            ("d", &[1000.0, 2000.0, 3000.0]),
+        ]);
+        let rd = tool
+            .process_chart_data(
+                &cd,
+                &RenderOptions {
+                    max_width: Some(340.0),
+                    downsample: true,
+                    ..opts
+                },
+            )
+            .unwrap();
+        let total = rd.items().last().unwrap();
+
+        assert_eq!(rd.items().len(), 3);
+        assert_eq!(
+            total.quartile(),
+            &Quartile::new(&[1.0, 2.0, 3.0, 100.0, 200.0, 300.0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn merge_outliers_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[
+            (
+                "a",
+                &[
+                    1.0, 1.0, 48.0, 52.0, 57.0, 61.0, 64.0, 72.0, 76.0, 77.0, 81.0, 85.0, 88.0,
+                ],
+            ),
+            (
+                "b",
+                &[
+                    1.0, 1.0, 1.0, 1.0, 80.0, 81.0, 82.0, 83.0, 84.0, 85.0, 86.0, 87.0, 88.0, 89.0,
+                    90.0, 91.0, 92.0, 93.0, 94.0, 95.0, 96.0, 97.0, 98.0, 99.0, 100.0,
+                ],
+            ),
+        ]);
+        let opts = RenderOptions {
+            merge_outliers: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let layout = rd.layout();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(layout.items[0].outliers.len(), 1);
+        assert_eq!(layout.items[0].outliers[0].count, 2);
+        assert_eq!(layout.items[1].outliers.len(), 1);
+        assert_eq!(layout.items[1].outliers[0].count, 4);
+        assert_eq!(document.matches(r#"class="outliers""#).count(), 2);
+        assert!(document.contains(r#"opacity="0.5""#));
+        assert!(document.contains(r#"opacity="1""#));
+    }
+
+    #[test]
+    fn target_band_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[-10.0, -5.0, 0.0, 5.0, 10.0])]);
+        let opts = RenderOptions {
+            target_band: Some((5.0, -5.0)),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        // Y axis runs from -10 at 440 to 10 at 40, so 20 pixels per unit
+        assert!(document
+            .contains(r#"<rect class="target-band" height="200" width="60" x="80" y="140"/>"#));
+        assert!(document.find("target-band\"").unwrap() < document.find("box-plot\"").unwrap());
+
+        let opts = RenderOptions {
+            target_band: Some((0.0, 100.0)),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert!(document
+            .contains(r#"<rect class="target-band" height="200" width="60" x="80" y="40"/>"#));
+    }
+
+    #[test]
+    fn minify_pretty_test() {
+        let logger = TestLogger::new();
+        let mut tool = BoxPlotChartTool::new(&logger);
+        let dir = TempDir::new("minify_pretty");
+
+        for option in ["--minify", "--pretty"] {
+            let args: Vec<std::ffi::OsString> = vec![
+                "".into(),
+                example().into(),
+                dir.join(format!("chart{}.svg", option)).into(),
+                option.into(),
+            ];
+
+            tool.run(args).unwrap();
+        }
+
+        let minified = std::fs::read_to_string(dir.join("chart--minify.svg")).unwrap();
+        let pretty = std::fs::read_to_string(dir.join("chart--pretty.svg")).unwrap();
+
+        assert!(!minified.contains('\n'));
+        assert!(minified.contains("><g class=\"box-plot\">"));
+        assert!(pretty.contains('\n'));
+        assert!(pretty.contains("\n  <polyline class=\"axis\""));
+        assert_eq!(whitespace::minify(&pretty), minified);
+    }
+
+    #[test]
+    fn background_rect_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 2.0, 3.0])]);
+        let opts = RenderOptions {
+            author: Some("Jane Doe".to_owned()),
+            background: Some("rgba(255,255,255,0.5)".to_owned()),
+            background_rect: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let first_child = document.lines().nth(1).unwrap();
+
+        assert_eq!(
+            first_child,
+            r#"<rect fill="rgba(255,255,255,0.5)" height="520" width="220" x="0" y="0"/>"#
+        );
+        assert!(!document.contains("background-color"));
+
+        let charts = [cd.clone(), cd.clone()];
+        let grid = tool.render_grid(&charts, 2, &opts).unwrap().to_string();
+        let stack = tool.render_stack(&charts, &opts).unwrap().to_string();
+
+        assert_eq!(
+            grid.lines().nth(1).unwrap(),
+            r#"<rect fill="rgba(255,255,255,0.5)" height="520" width="440" x="0" y="0"/>"#
+        );
+        assert!(stack
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with(r#"<rect fill="rgba(255,255,255,0.5)" height=""#));
+
+        for document in [grid, stack] {
+            assert!(!document.contains("background-color"));
+        }
+    }
+
+    #[test]
+    fn error_bars_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let cd = chart_data(&[("a", &values)]);
+        let opts = RenderOptions {
+            error_bars: true,
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let layout = rd.layout();
+        let error_bar = layout.items[0].error_bar.as_ref().unwrap();
+        let std_dev = Quartile::new(&values)
+            .unwrap()
+            .std_dev(VarianceKind::Sample)
+            .unwrap();
+        let y_scale = rd.y_axis_height() / (rd.y_axis_range().1 - rd.y_axis_range().0);
+        let to_y =
+            |n: f64| layout.height - rd.gutter().bottom - (n - rd.y_axis_range().0) * y_scale;
+        let document = tool.render_chart(&rd).unwrap().to_string();
+
+        assert_eq!(error_bar.mean_y, to_y(5.0));
+        assert_eq!(error_bar.top, to_y(5.0 + std_dev));
+        assert_eq!(error_bar.bottom, to_y(5.0 - std_dev));
+        assert!(document.contains(r#"class="error-bar""#));
+        assert!(document.contains(r#"class="mean""#));
+
+        let rd = tool.process_chart_data(&cd, &Default::default()).unwrap();
+
+        assert!(rd.layout().items[0].error_bar.is_none());
+    }
+
+    #[test]
+    fn y_ticks_at_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[1.0, 3.0, 6.0, 9.0])]);
+        let opts = RenderOptions {
+            y_ticks: Some(vec![1.0, 2.0, 5.0, 10.0, 50.0]),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let layout = rd.layout();
+        let document = tool.render_chart(&rd).unwrap().to_string();
+        let y_labels = &document[document.find(r#"class="labels y-labels""#).unwrap()..];
+        let y_labels = &y_labels[..y_labels.find("</g>").unwrap()];
+
+        assert_eq!(rd.y_axis_range(), (1.0, 9.0));
+        assert_eq!(
+            layout.ticks.iter().map(|t| t.value).collect::<Vec<f64>>(),
+            vec![1.0, 2.0, 5.0]
+        );
+        assert_eq!(y_labels.matches("<text").count(), 3);
+        assert!(y_labels.contains(">\n5.0\n<"));
+        assert!(!y_labels.contains(">\n10.0\n<"));
+    }
+
+    #[test]
+    fn y_axis_intervals_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[0.0, 25.0, 50.0, 75.0, 100.0])]);
+        let tick_count = |y_axis_height: f64, y_axis_intervals: Option<usize>| {
+            let opts = RenderOptions {
+                y_axis_height: Some(y_axis_height),
+                y_axis_intervals,
+                ..Default::default()
+            };
+
+            tool.process_chart_data(&cd, &opts)
+                .unwrap()
+                .layout()
+                .ticks
+                .len()
+        };
+        let short = tick_count(100.0, None);
+        let default = tick_count(400.0, None);
+        let tall = tick_count(1000.0, None);
+
+        assert_eq!(default, 21);
+        assert!(short < default && default < tall);
+
+        for (ticks, height) in [(short, 100.0), (default, 400.0), (tall, 1000.0)] {
+            assert!(ticks >= 3);
+            assert!((ticks - 1) as f64 * Y_AXIS_TICK_SPACING <= height);
+        }
+
+        assert_eq!(tick_count(1000.0, Some(4)), 5);
+        assert!(tool
+            .process_chart_data(
+                &cd,
+                &RenderOptions {
+                    y_axis_intervals: Some(0),
+                    ..Default::default()
+                }
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn max_ticks_test() {
+        let logger = TestLogger::new();
+        let tool = BoxPlotChartTool::new(&logger);
+        let cd = chart_data(&[("a", &[0.0, 500_000.0, 1_000_000.0])]);
+        let opts = RenderOptions {
+            y_ticks: Some((0..=1_000_000).step_by(10).map(|n| n as f64).collect()),
+            ..Default::default()
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+        let ticks = rd.layout().ticks;
+
+        assert_eq!(ticks.len(), DEFAULT_MAX_TICKS);
+        assert_eq!(ticks[0].value, 0.0);
+        assert_eq!(ticks[1].value, 10_000.0);
+        assert_eq!(
+            logger.warnings(),
+            vec!["Showing 100 of 100001 Y axis ticks".to_owned()]
+        );
+
+        let opts = RenderOptions {
+            max_ticks: Some(5),
+            ..opts
+        };
+        let rd = tool.process_chart_data(&cd, &opts).unwrap();
+
+        assert_eq!(
+            rd.layout()
+                .ticks
+                .iter()
+                .map(|tick| tick.value)
+                .collect::<Vec<f64>>(),
+            vec![0.0, 200_000.0, 400_000.0, 600_000.0, 800_000.0]
+        );
+    }
+
+    #[test]
+    fn mkdir_test() {
+        let logger = TestLogger::new();
+        let mut tool = BoxPlotChartTool::new(&logger);
+        let dir = TempDir::new("mkdir");
+        let svg_path = dir.join("nested/deeper/chart.svg");
+        let args = |mkdir: bool| {
+            let mut args: Vec<std::ffi::OsString> =
+                vec!["".into(), example().into(), svg_path.clone().into()];
+
+            if mkdir {
+                args.push("--mkdir".into());
+            }
+
+            args
+        };
+
+        let _ = std::fs::remove_dir_all(svg_path.parent().unwrap().parent().unwrap());
+
+        let err = tool.run(args(false)).unwrap_err();
+
+        assert!(err.to_string().contains("nested/deeper' does not exist"));
+        assert_eq!(error::exit_code(err.as_ref()), ErrorKind::Write.exit_code());
+
+        tool.run(args(true)).unwrap();
+
+        assert!(svg_path.exists());
+    }
+
+    #[test]
+    fn dual_theme_test() {
+        let logger = TestLogger::new();
+        let mut tool = BoxPlotChartTool::new(&logger);
+        let dir = TempDir::new("dual_theme");
+        let args: Vec<std::ffi::OsString> = vec![
+            "".into(),
+            example().into(),
+            dir.join("chart.svg").into(),
+            "--dual-theme".into(),
+        ];
 
         tool.run(args).unwrap();
+
+        let light = std::fs::read_to_string(dir.join("chart-light.svg")).unwrap();
+        let dark = std::fs::read_to_string(dir.join("chart-dark.svg")).unwrap();
+
+        assert!(!dir.join("chart.svg").exists());
+        assert!(light.contains("background-color: white;"));
+        assert!(dark.contains(&format!("background-color: {};", Theme::Dark.background())));
+        assert!(dark.contains(&format!(
+            ".axis{{fill:none;stroke:{};",
+            Theme::Dark.foreground()
+        )));
+        assert!(tool
+            .run(vec!["".into(), example().into(), "--dual-theme".into()])
+            .is_err());
+    }
+
+    #[test]
+    fn per_page_test() {
+        let logger = TestLogger::new();
+        let mut tool = BoxPlotChartTool::new(&logger);
+        let dir = TempDir::new("per_page");
+        let items: Vec<String> = (0..25)
+            .map(|i| format!(r#"{{ key: "k{}", values: [1, 2, 3] }}"#, i))
+            .collect();
+        let input = dir.join("input.json5");
+
+        std::fs::write(
+            &input,
+            format!(
+                r#"{{ title: "Pages", units: "ms", data: [{}] }}"#,
+                items.join(", ")
+            ),
+        )
+        .unwrap();
+        tool.run(vec![
+            "".into(),
+            input.clone().into(),
+            dir.join("chart.svg").into(),
+            "--per-page".into(),
+            "10".into(),
+        ])
+        .unwrap();
+
+        for (page, count) in [(1, 10), (2, 10), (3, 5)] {
+            let svg = std::fs::read_to_string(dir.join(format!("chart-{}.svg", page))).unwrap();
+
+            assert_eq!(svg.matches(r#"<g class="box-plot""#).count(), count);
+        }
+
+        assert!(!dir.join("chart-4.svg").exists());
+        assert!(std::fs::read_to_string(dir.join("chart-3.svg"))
+            .unwrap()
+            .contains(">\nk24\n<"));
+
+        tool.run(vec![
+            "".into(),
+            input.into(),
+            dir.join("page{page}of3.svg").into(),
+            "--per-page".into(),
+            "10".into(),
+        ])
+        .unwrap();
+
+        assert!(dir.join("page3of3.svg").exists());
+    }
+
+    #[test]
+    fn csv_stdin_format_test() {
+        let csv = "north, 1, 2, 3\nsouth,4,5,6,7\n";
+        let cd = BoxPlotChartTool::read_chart_file(
+            Box::new(io::Cursor::new(csv)),
+            Some(InputFormat::Csv),
+            &CsvColumns::default(),
+        )
+        .unwrap();
+
+        assert_eq!(cd.title, "");
+        assert_eq!(cd.data.len(), 2);
+        assert_eq!(cd.data[0].key, "north");
+        assert_eq!(cd.data[0].values, vec![1.0, 2.0, 3.0]);
+        assert_eq!(cd.data[1].key, "south");
+        assert_eq!(cd.data[1].values, vec![4.0, 5.0, 6.0, 7.0]);
+        assert!(BoxPlotChartTool::read_chart_file(
+            Box::new(io::Cursor::new(csv)),
+            Some(InputFormat::Json5),
+            &CsvColumns::default(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn csv_value_columns_test() {
+        let csv = "host,region,run1,run2,run3,notes\na,us,1,2,3,99\nb,eu,4,,6,99\n";
+        let columns = CsvColumns {
+            key: Some("region".to_owned()),
+            values: vec!["run1".to_owned(), "run2".to_owned(), "run3".to_owned()],
+        };
+        let cd = BoxPlotChartTool::read_chart_file(
+            Box::new(io::Cursor::new(csv)),
+            Some(InputFormat::Csv),
+            &columns,
+        )
+        .unwrap();
+
+        assert_eq!(cd.data.len(), 2);
+        assert_eq!(cd.data[0].key, "us");
+        assert_eq!(cd.data[0].values, vec![1.0, 2.0, 3.0]);
+        assert_eq!(cd.data[1].key, "eu");
+        assert_eq!(cd.data[1].values, vec![4.0, 6.0]);
+
+        let columns = CsvColumns {
+            values: vec!["missing".to_owned()],
+            ..Default::default()
+        };
+
+        assert!(BoxPlotChartTool::read_chart_file(
+            Box::new(io::Cursor::new(csv)),
+            Some(InputFormat::Csv),
+            &columns,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn sniff_format_test() {
+        let json5 = "{title: 'T', units: 'U', data: [{key: 'a', values: [1, 2, 3]}]}";
+        let cd = BoxPlotChartTool::read_chart_file(
+            Box::new(io::Cursor::new(json5)),
+            None,
+            &CsvColumns::default(),
+        )
+        .unwrap();
+
+        assert_eq!(cd.title, "T");
+        assert_eq!(InputFormat::sniff("  \n[1]"), InputFormat::Json5);
+        assert_eq!(InputFormat::sniff("a,1,2,3"), InputFormat::Csv);
+        assert_eq!(
+            InputFormat::from_path(Path::new("data.CSV")),
+            Some(InputFormat::Csv)
+        );
     }
 }
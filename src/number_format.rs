@@ -0,0 +1,137 @@
+use std::error::Error;
+
+/// Locale dependent decimal and thousands separators for numeric labels
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberFormat {
+    decimal_separator: char,
+    thousands_separator: Option<char>,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat {
+            decimal_separator: '.',
+            thousands_separator: None,
+        }
+    }
+}
+
+impl NumberFormat {
+    pub fn new(decimal_separator: char, thousands_separator: Option<char>) -> NumberFormat {
+        NumberFormat {
+            decimal_separator,
+            thousands_separator,
+        }
+    }
+
+    /// The number format for a language tag such as `de` or `en-US`
+    pub fn for_locale(locale: &str) -> Result<NumberFormat, Box<dyn Error>> {
+        let language = locale
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match language.as_str() {
+            "en" | "ja" | "ko" | "zh" => Ok(NumberFormat::new('.', Some(','))),
+            "de" | "da" | "es" | "id" | "it" | "nl" | "pt" | "tr" => {
+                Ok(NumberFormat::new(',', Some('.')))
+            }
+            "fr" | "cs" | "fi" | "nb" | "pl" | "ru" | "sv" | "uk" => {
+                Ok(NumberFormat::new(',', Some('\u{202f}')))
+            }
+            _ => Err(From::from(format!("Unsupported locale '{}'", locale))),
+        }
+    }
+
+    /// Format `value` with `decimal_places` digits after the decimal separator
+    pub fn format(&self, value: f64, decimal_places: usize) -> String {
+        let formatted = format!("{0:.1$}", value, decimal_places);
+        let (sign, digits) = match formatted.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", formatted.as_str()),
+        };
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (digits, None),
+        };
+        let mut result = String::from(sign);
+
+        for (i, c) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i).is_multiple_of(3) {
+                if let Some(separator) = self.thousands_separator {
+                    result.push(separator);
+                }
+            }
+
+            result.push(c);
+        }
+
+        if let Some(fraction) = fraction {
+            result.push(self.decimal_separator);
+            result.push_str(fraction);
+        }
+
+        result
+    }
+
+    /// Format `value` rounded to `significant_figures` significant figures, with zeros in
+    /// place of the digits rounded away from large numbers
+    pub fn format_significant(&self, value: f64, significant_figures: usize) -> String {
+        let magnitude = |n: f64| {
+            if n == 0.0 || !n.is_finite() {
+                0
+            } else {
+                n.abs().log10().floor() as i32
+            }
+        };
+        let scale = 10_f64.powi(significant_figures as i32 - 1 - magnitude(value));
+        let rounded = (value * scale).round() / scale;
+        // Rounding up can add a digit, as with 9.96 to 10, leaving one fewer decimal place
+        let decimal_places = (significant_figures as i32 - 1 - magnitude(rounded)).max(0);
+
+        self.format(rounded, decimal_places as usize)
+    }
+
+    /// Format `value` in scientific notation, keeping the precision it would have with
+    /// `decimal_places` digits after the decimal separator
+    pub fn format_scientific(&self, value: f64, decimal_places: usize) -> String {
+        let exponent = if value == 0.0 {
+            0
+        } else {
+            value.abs().log10().floor() as i32
+        };
+        let mantissa_places = (decimal_places as i32 + exponent).max(0) as usize;
+
+        format!("{0:.1$e}", value, mantissa_places)
+            .replace('.', &self.decimal_separator.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_test() {
+        let de = NumberFormat::for_locale("de").unwrap();
+        let en = NumberFormat::for_locale("en-US").unwrap();
+
+        assert_eq!(de.format(1234.5, 1), "1.234,5");
+        assert_eq!(de.format(-1234567.0, 0), "-1.234.567");
+        assert_eq!(en.format(1234.5, 2), "1,234.50");
+        assert_eq!(en.format(999.0, 0), "999");
+        assert_eq!(NumberFormat::default().format(1234.5, 1), "1234.5");
+        assert!(NumberFormat::for_locale("xx").is_err());
+        assert_eq!(en.format_scientific(0.00000123, 8), "1.23e-6");
+        assert_eq!(
+            NumberFormat::default().format_significant(1234.5, 2),
+            "1200"
+        );
+        assert_eq!(en.format_significant(-987654.0, 3), "-988,000");
+        assert_eq!(de.format_significant(0.0012345, 3), "0,00123");
+        assert_eq!(en.format_significant(9.96, 2), "10");
+        assert_eq!(en.format_significant(0.0, 2), "0.0");
+        assert_eq!(de.format_scientific(-0.0000012, 7), "-1,2e-6");
+    }
+}
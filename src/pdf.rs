@@ -0,0 +1,62 @@
+use std::error::Error;
+use svg2pdf::usvg::{self, fontdb, PostProcessingSteps, TreeParsing, TreePostProc};
+
+/// Pixels per inch of an SVG, so that the page is sized in points at 72 per inch
+const DPI: f32 = 96.0;
+
+/// Write an SVG document as a single page PDF of the same size, filled with `background`.
+/// The chart stays as vector paths, with its text converted to outlines using the system
+/// fonts.
+pub fn from_svg(svg: &str, background: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let options = usvg::Options::default();
+    let mut tree = usvg::Tree::from_str(svg, &options)?;
+    let rect = tree.view_box.rect;
+    // The background is a style on the root element, which usvg ignores, so draw it
+    // beneath the chart as a rectangle covering the view box
+    let mut backdrop = usvg::Tree::from_str(
+        &format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{0} {1} {2} {3}"><rect x="{0}" y="{1}" width="{2}" height="{3}" fill="{4}"/></svg>"#,
+            rect.x(),
+            rect.y(),
+            rect.width(),
+            rect.height(),
+            background
+        ),
+        &options,
+    )?;
+    let mut fonts = fontdb::Database::new();
+
+    fonts.load_system_fonts();
+    tree.root
+        .children
+        .splice(0..0, backdrop.root.children.drain(..));
+    tree.postprocess(PostProcessingSteps::default(), &fonts);
+
+    Ok(svg2pdf::convert_tree(
+        &tree,
+        svg2pdf::Options {
+            dpi: DPI,
+            ..Default::default()
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_svg_test() {
+        let pdf = from_svg(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="96" height="48"><rect width="10" height="10"/></svg>"#,
+            "rgb(24,24,24)",
+        )
+        .unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert!(text.contains("/MediaBox [0 0 72 36]"));
+        // No raster image of the chart, only vector content
+        assert!(!text.contains("/Subtype /Image"));
+    }
+}
@@ -1,64 +1,191 @@
 use std::error::Error;
 
+/// The floating point type in which quartile values are stored; `f32` when the `f32`
+/// feature is enabled, otherwise `f64`.  The feature affects quartile storage only: values
+/// are always passed in and returned as `f64`, and layout and rendering are done in `f64`.
+#[cfg(feature = "f32")]
+type Float = f32;
+#[cfg(not(feature = "f32"))]
+type Float = f64;
+
+/// Widen a stored value for use in calculations
+#[allow(clippy::useless_conversion)]
+fn widen(n: Float) -> f64 {
+    f64::from(n)
+}
+
+/// How the whisker endpoints, and therefore the outliers, are determined
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum WhiskerMode {
+    /// Whiskers extend to the furthest values within 1.5 IQR of the box
+    #[default]
+    Tukey,
+    /// Whiskers extend to the furthest values within the given lower and upper percentiles
+    /// (0 to 100)
+    Percentile(f64, f64),
+}
+
+/// Whether variance treats the values as a whole population or a sample of one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarianceKind {
+    /// Divide by the number of values
+    Population,
+    /// Divide by one less than the number of values (Bessel's correction)
+    Sample,
+}
+
+/// Where a value falls relative to the fences of a set of quartiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Classification {
+    /// Below the lower fence
+    LowerOutlier,
+    /// Between the fences, inclusive
+    Normal,
+    /// Above the upper fence
+    UpperOutlier,
+}
+
+/// How the lower and upper quartiles are chosen from the sorted values
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuartileMethod {
+    /// Medians of the lower and upper halves, excluding the median of odd sized data.  With
+    /// only 3 values each half is a single value, so the quartiles are the smallest and
+    /// largest values and the box spans the whole range.
+    #[default]
+    Exclusive,
+    /// Medians of the lower and upper halves, including the median of odd sized data
+    Inclusive,
+    /// Linearly interpolated 25th and 75th percentiles
+    Linear,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Quartile {
-    lower_outliers: Vec<f64>,
-    lower_fence: f64,
-    min_before_lower_fence: f64,
-    lower_median: f64,
-    median: f64,
-    upper_median: f64,
-    max_before_upper_fence: f64,
-    upper_fence: f64,
-    upper_outliers: Vec<f64>,
-    iqr: f64,
+    values: Vec<Float>,
+    method: QuartileMethod,
+    whisker_mode: WhiskerMode,
+    lower_outliers: Vec<Float>,
+    lower_fence: Float,
+    min_before_lower_fence: Float,
+    lower_median: Float,
+    median: Float,
+    upper_median: Float,
+    max_before_upper_fence: Float,
+    upper_fence: Float,
+    upper_outliers: Vec<Float>,
+    iqr: Float,
+    sample_size: Option<usize>,
 }
 
 impl Quartile {
     pub fn new(values: &[f64]) -> Result<Quartile, Box<dyn Error>> {
+        Self::with_whisker_mode(values, WhiskerMode::Tukey)
+    }
+
+    pub fn with_whisker_mode(
+        values: &[f64],
+        whisker_mode: WhiskerMode,
+    ) -> Result<Quartile, Box<dyn Error>> {
+        Self::with_method(values, QuartileMethod::default(), whisker_mode)
+    }
+
+    /// Quartiles of `values` by `method`, which needs at least 3 values so that there is a
+    /// value either side of the median
+    pub fn with_method(
+        values: &[f64],
+        method: QuartileMethod,
+        whisker_mode: WhiskerMode,
+    ) -> Result<Quartile, Box<dyn Error>> {
         if values.len() < 3 {
-            return Err(From::from(format!(
-                "Minimum of 3 values needed for a quartile range"
-            )));
+            return Err(From::from(
+                "Minimum of 3 values needed for a quartile range",
+            ));
         }
 
-        let mut arr = values.to_vec();
+        if values.iter().any(|n| n.is_nan()) {
+            return Err(From::from("Quartile values cannot be NaN"));
+        }
 
-        arr.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        if let WhiskerMode::Percentile(lower, upper) = whisker_mode {
+            if !(0.0..=100.0).contains(&lower) || !(0.0..=100.0).contains(&upper) || lower >= upper
+            {
+                return Err(From::from(format!(
+                    "Whisker percentiles {} and {} must be ascending and between 0 and 100",
+                    lower, upper
+                )));
+            }
+        }
 
-        let len = arr.len();
-        let midpoint = len / 2;
-        let median: f64;
-        let upper_median: f64;
+        let mut arr: Vec<Float> = values.iter().map(|n| *n as Float).collect();
 
-        if len % 2 == 0 {
-            // Even sized array
-            median = (arr[midpoint - 1] + arr[midpoint]) / 2.0;
-            upper_median = arr[midpoint + midpoint / 2];
-        } else {
-            // Odd sized array
-            median = arr[midpoint];
-            upper_median = arr[midpoint + 1 + midpoint / 2];
-        }
+        arr.sort_by(Float::total_cmp);
 
-        let lower_median = arr[midpoint / 2];
+        let len = arr.len();
+        let midpoint = len / 2;
+        let median = Self::median_of(&arr);
+        // The halves mirror each other, so the quartiles are always symmetric in rank
+        let (lower_median, upper_median) = match method {
+            QuartileMethod::Exclusive => (
+                Self::median_of(&arr[..midpoint]),
+                Self::median_of(&arr[len - midpoint..]),
+            ),
+            QuartileMethod::Inclusive => (
+                Self::median_of(&arr[..len - midpoint]),
+                Self::median_of(&arr[midpoint..]),
+            ),
+            QuartileMethod::Linear => {
+                (Self::interpolate(&arr, 25.0), Self::interpolate(&arr, 75.0))
+            }
+        };
         let iqr = upper_median - lower_median;
-        let lower_fence = lower_median - 1.5f64 * iqr;
-        let upper_fence = upper_median + 1.5f64 * iqr;
-        let lower_outliers: Vec<f64> = arr
+        let (lower_fence, upper_fence) = match whisker_mode {
+            WhiskerMode::Tukey => (lower_median - 1.5 * iqr, upper_median + 1.5 * iqr),
+            WhiskerMode::Percentile(lower, upper) => (
+                Self::interpolate(&arr, lower),
+                Self::interpolate(&arr, upper),
+            ),
+        };
+        // Outliers are taken from the sorted values so that both are always ascending
+        let lower_outliers: Vec<Float> = arr
             .iter()
             .take_while(|n| **n < lower_fence)
             .cloned()
             .collect();
-        let upper_outliers: Vec<f64> = arr
+        let upper_outliers: Vec<Float> = arr
             .iter()
-            .take_while(|n| **n > upper_fence)
+            .skip_while(|n| **n <= upper_fence)
             .cloned()
             .collect();
-        let min_before_lower_fence = arr[lower_outliers.len()];
-        let max_before_upper_fence = arr[arr.len() - upper_outliers.len() - 1];
+        // Whiskers end on the most extreme values within the fences, so an interpolated
+        // percentile is pulled in to a real value, but never reach inside the box, even when
+        // nearly every value is an outlier
+        let min_before_lower_fence =
+            arr.iter()
+                .find(|n| **n >= lower_fence)
+                .map_or(lower_median, |n| {
+                    if *n <= lower_median {
+                        *n
+                    } else {
+                        lower_median
+                    }
+                });
+        let max_before_upper_fence =
+            arr.iter()
+                .rev()
+                .find(|n| **n <= upper_fence)
+                .map_or(upper_median, |n| {
+                    if *n >= upper_median {
+                        *n
+                    } else {
+                        upper_median
+                    }
+                });
 
         Ok(Quartile {
+            sample_size: Some(arr.len()),
+            values: arr,
+            method,
+            whisker_mode,
             lower_outliers,
             lower_fence,
             min_before_lower_fence,
@@ -72,67 +199,475 @@ impl Quartile {
         })
     }
 
+    /// Build a quartile from summary statistics without the underlying values, such as those
+    /// estimated by `ApproxQuartile`.  Only the extremes can be reported as outliers, and as
+    /// there are no values `values`, `percentile`, `mean` and `variance` return `None`.
+    pub fn from_summary(
+        min: f64,
+        lower_median: f64,
+        median: f64,
+        upper_median: f64,
+        max: f64,
+    ) -> Result<Quartile, Box<dyn Error>> {
+        let summary: Vec<Float> = [min, lower_median, median, upper_median, max]
+            .iter()
+            .map(|n| *n as Float)
+            .collect();
+
+        if summary.iter().any(|n| n.is_nan()) {
+            return Err(From::from("Quartile values cannot be NaN"));
+        }
+
+        if summary.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(From::from("Quartile summary values must be ascending"));
+        }
+
+        let (min, lower_median, median, upper_median, max) =
+            (summary[0], summary[1], summary[2], summary[3], summary[4]);
+        let iqr = upper_median - lower_median;
+        let lower_fence = lower_median - 1.5 * iqr;
+        let upper_fence = upper_median + 1.5 * iqr;
+        let lower_outliers: Vec<Float> = [min].into_iter().filter(|n| *n < lower_fence).collect();
+        let upper_outliers: Vec<Float> = [max].into_iter().filter(|n| *n > upper_fence).collect();
+
+        Ok(Quartile {
+            values: vec![],
+            method: QuartileMethod::Linear,
+            whisker_mode: WhiskerMode::Tukey,
+            min_before_lower_fence: if lower_outliers.is_empty() {
+                min
+            } else {
+                lower_fence.min(lower_median)
+            },
+            max_before_upper_fence: if upper_outliers.is_empty() {
+                max
+            } else {
+                upper_fence.max(upper_median)
+            },
+            lower_outliers,
+            lower_fence,
+            lower_median,
+            median,
+            upper_median,
+            upper_fence,
+            upper_outliers,
+            iqr,
+            sample_size: None,
+        })
+    }
+
+    /// Give the number of values summarized by a quartile built with `from_summary`
+    pub fn with_sample_size(mut self, sample_size: usize) -> Quartile {
+        self.sample_size = Some(sample_size);
+        self
+    }
+
+    /// Estimate quartiles from a histogram of `(bin_center, count)` pairs, treating every value
+    /// in a bin as its center.  The quartiles are linearly interpolated between the ranks of
+    /// the cumulative counts, as with `QuartileMethod::Linear`, without expanding the bins.
+    pub fn from_histogram(bins: &[(f64, u64)]) -> Result<Quartile, Box<dyn Error>> {
+        if bins.iter().any(|(center, _)| center.is_nan()) {
+            return Err(From::from("Quartile values cannot be NaN"));
+        }
+
+        let mut bins: Vec<(f64, u64)> = bins.iter().filter(|bin| bin.1 > 0).cloned().collect();
+
+        bins.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let total: u64 = bins.iter().map(|bin| bin.1).sum();
+
+        if total < 3 {
+            return Err(From::from(
+                "Minimum of 3 values needed for a quartile range",
+            ));
+        }
+
+        // The center of the bin holding the value of zero-based rank `rank`
+        let value_at = |rank: u64| {
+            let mut cumulative = 0;
+
+            bins.iter()
+                .find(|(_, count)| {
+                    cumulative += count;
+                    rank < cumulative
+                })
+                .map_or(bins[bins.len() - 1].0, |bin| bin.0)
+        };
+        let percentile = |p: f64| {
+            let rank = p / 100.0 * (total - 1) as f64;
+            let lower = value_at(rank.floor() as u64);
+            let upper = value_at(rank.ceil() as u64);
+
+            lower + (upper - lower) * rank.fract()
+        };
+
+        Ok(Self::from_summary(
+            bins[0].0,
+            percentile(25.0),
+            percentile(50.0),
+            percentile(75.0),
+            bins[bins.len() - 1].0,
+        )?
+        .with_sample_size(total as usize))
+    }
+
+    /// Sort values into ascending order using a total ordering, so that `-0.0` always
+    /// sorts before `0.0` and the result never depends on the input order of ties.
+    pub fn sort(values: &mut [f64]) {
+        values.sort_by(f64::total_cmp);
+    }
+
+    /// Median of already sorted, non-empty values
+    fn median_of(sorted: &[Float]) -> Float {
+        let midpoint = sorted.len() / 2;
+
+        if sorted.len().is_multiple_of(2) {
+            (sorted[midpoint - 1] + sorted[midpoint]) / 2.0
+        } else {
+            sorted[midpoint]
+        }
+    }
+
+    /// Linearly interpolated percentile `p` (0 to 100) of already sorted values
+    fn interpolate(sorted: &[Float], p: f64) -> Float {
+        let rank = p / 100.0 * (sorted.len() - 1) as f64;
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+
+        sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64) as Float
+    }
+
+    /// The `p`th percentile (0 to 100) of the values, interpolated between the closest ranks,
+    /// or `None` for a quartile built by `from_summary`, which has no values
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        self.has_values()
+            .then(|| widen(Self::interpolate(&self.values, p.clamp(0.0, 100.0))))
+    }
+
+    /// The mean of all the values, including outliers, or `None` for a quartile built by
+    /// `from_summary`
+    pub fn mean(&self) -> Option<f64> {
+        self.has_values()
+            .then(|| self.values.iter().map(|n| widen(*n)).sum::<f64>() / self.values.len() as f64)
+    }
+
+    /// The variance of all the values, including outliers, or `None` for a quartile built by
+    /// `from_summary`
+    pub fn variance(&self, kind: VarianceKind) -> Option<f64> {
+        let mean = self.mean()?;
+        let sum_of_squares: f64 = self.values.iter().map(|n| (widen(*n) - mean).powi(2)).sum();
+        let divisor = match kind {
+            VarianceKind::Population => self.values.len(),
+            VarianceKind::Sample => self.values.len() - 1,
+        };
+
+        Some(sum_of_squares / divisor as f64)
+    }
+
+    /// The standard deviation of all the values, including outliers, or `None` for a
+    /// quartile built by `from_summary`
+    pub fn std_dev(&self, kind: VarianceKind) -> Option<f64> {
+        self.variance(kind).map(f64::sqrt)
+    }
+
+    /// All of the values in ascending order, including outliers, or `None` for a quartile
+    /// built by `from_summary`
+    pub fn values(&self) -> Option<Vec<f64>> {
+        self.has_values()
+            .then(|| self.values.iter().map(|n| widen(*n)).collect())
+    }
+
+    /// Whether the quartile holds the values it was computed from, which one built by
+    /// `from_summary` does not
+    pub fn has_values(&self) -> bool {
+        !self.values.is_empty()
+    }
+
+    /// The number of values held, including outliers, which is zero for a quartile built by
+    /// `from_summary`
+    pub fn count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The number of values the quartile was computed from, which for one built by
+    /// `from_summary` is unknown unless given with `with_sample_size`
+    pub fn sample_size(&self) -> Option<usize> {
+        self.sample_size
+    }
+
+    pub fn method(&self) -> QuartileMethod {
+        self.method
+    }
+
+    pub fn whisker_mode(&self) -> WhiskerMode {
+        self.whisker_mode
+    }
+
+    /// Values below the lower fence, in ascending order
     pub fn lower_outliers(&self) -> Vec<f64> {
-        self.lower_outliers.clone()
+        self.lower_outliers.iter().map(|n| widen(*n)).collect()
     }
 
     pub fn lower_fence(&self) -> f64 {
-        self.lower_fence
+        widen(self.lower_fence)
     }
 
     pub fn min_before_lower_fence(&self) -> f64 {
-        self.min_before_lower_fence
+        widen(self.min_before_lower_fence)
     }
 
     pub fn lower_median(&self) -> f64 {
-        self.lower_median
+        widen(self.lower_median)
     }
 
     pub fn median(&self) -> f64 {
-        self.median
+        widen(self.median)
     }
 
     pub fn upper_median(&self) -> f64 {
-        self.upper_median
+        widen(self.upper_median)
     }
 
     pub fn max_before_upper_fence(&self) -> f64 {
-        self.max_before_upper_fence
+        widen(self.max_before_upper_fence)
+    }
+
+    /// The low and high ends of the whiskers under the whisker mode, which are the most
+    /// extreme values within the fences; in percentile mode the fences are the percentiles.
+    /// Neither reaches inside the box.
+    pub fn whisker_bounds(&self) -> (f64, f64) {
+        (
+            widen(self.min_before_lower_fence),
+            widen(self.max_before_upper_fence),
+        )
     }
 
     pub fn upper_fence(&self) -> f64 {
-        self.upper_fence
+        widen(self.upper_fence)
     }
 
     pub fn iqr(&self) -> f64 {
-        self.iqr
+        widen(self.iqr)
     }
 
+    /// Classify any value, not only those the quartiles were calculated from, against the
+    /// fences in the same way as the outliers were chosen
+    pub fn classify(&self, value: f64) -> Classification {
+        if value < self.lower_fence() {
+            Classification::LowerOutlier
+        } else if value > self.upper_fence() {
+            Classification::UpperOutlier
+        } else {
+            Classification::Normal
+        }
+    }
+
+    /// Values above the upper fence, in ascending order
     pub fn upper_outliers(&self) -> Vec<f64> {
-        self.upper_outliers.clone()
+        self.upper_outliers.iter().map(|n| widen(*n)).collect()
     }
 
     pub fn min_value(&self) -> f64 {
         if self.lower_outliers.is_empty() {
-            self.min_before_lower_fence
+            widen(self.min_before_lower_fence)
         } else {
-            self.lower_outliers[0]
+            widen(self.lower_outliers[0])
         }
     }
 
     pub fn max_value(&self) -> f64 {
         if self.upper_outliers.is_empty() {
-            self.max_before_upper_fence
+            widen(self.max_before_upper_fence)
         } else {
-            *self.upper_outliers.last().unwrap()
+            widen(*self.upper_outliers.last().unwrap())
+        }
+    }
+}
+
+/// A streaming estimate of the quartiles of values too numerous to hold in memory, using
+/// the P² algorithm of Jain and Chlamtac, which keeps five markers per quantile
+#[derive(Debug, Clone)]
+pub struct ApproxQuartile {
+    count: usize,
+    min: f64,
+    max: f64,
+    estimators: [P2Estimator; 3],
+}
+
+impl Default for ApproxQuartile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApproxQuartile {
+    pub fn new() -> ApproxQuartile {
+        ApproxQuartile {
+            count: 0,
+            min: f64::MAX,
+            max: f64::MIN,
+            estimators: [
+                P2Estimator::new(0.25),
+                P2Estimator::new(0.5),
+                P2Estimator::new(0.75),
+            ],
+        }
+    }
+
+    /// Add a value to the estimate; NaN values are ignored
+    pub fn push(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+
+        for estimator in self.estimators.iter_mut() {
+            estimator.push(value);
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The estimated quartiles, which are exact while there are five values or fewer
+    pub fn to_quartile(&self) -> Result<Quartile, Box<dyn Error>> {
+        if self.count < 3 {
+            return Err(From::from(
+                "Minimum of 3 values needed for a quartile range",
+            ));
+        }
+
+        if self.count <= 5 {
+            return Quartile::new(&self.estimators[0].markers[..self.count]);
+        }
+
+        Ok(Quartile::from_summary(
+            self.min,
+            self.estimators[0].estimate(),
+            self.estimators[1].estimate(),
+            self.estimators[2].estimate(),
+            self.max,
+        )?
+        .with_sample_size(self.count))
+    }
+}
+
+impl Extend<f64> for ApproxQuartile {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
         }
     }
 }
 
+/// P² marker heights and positions for a single quantile `p`
+#[derive(Debug, Clone)]
+struct P2Estimator {
+    p: f64,
+    count: usize,
+    markers: [f64; 5],
+    positions: [f64; 5],
+    desired: [f64; 5],
+}
+
+impl P2Estimator {
+    fn new(p: f64) -> P2Estimator {
+        P2Estimator {
+            p,
+            count: 0,
+            markers: [0.0; 5],
+            positions: [0.0, 1.0, 2.0, 3.0, 4.0],
+            desired: [0.0, 2.0 * p, 4.0 * p, 2.0 + 2.0 * p, 4.0],
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        if self.count < 5 {
+            self.markers[self.count] = value;
+            self.count += 1;
+
+            if self.count == 5 {
+                self.markers.sort_by(f64::total_cmp);
+            } else {
+                self.markers[..self.count].sort_by(f64::total_cmp);
+            }
+
+            return;
+        }
+
+        self.count += 1;
+
+        let q = &mut self.markers;
+        let k = if value < q[0] {
+            q[0] = value;
+            0
+        } else if value >= q[4] {
+            q[4] = value;
+            3
+        } else {
+            (1..5).find(|i| value < q[*i]).unwrap() - 1
+        };
+        let increments = [0.0, self.p / 2.0, self.p, (1.0 + self.p) / 2.0, 1.0];
+
+        for i in k + 1..5 {
+            self.positions[i] += 1.0;
+        }
+
+        for (desired, increment) in self.desired.iter_mut().zip(increments) {
+            *desired += increment;
+        }
+
+        for i in 1..4 {
+            let n = &mut self.positions;
+            let d = self.desired[i] - n[i];
+
+            if (d >= 1.0 && n[i + 1] - n[i] > 1.0) || (d <= -1.0 && n[i - 1] - n[i] < -1.0) {
+                let d = d.signum();
+                let parabolic = q[i]
+                    + d / (n[i + 1] - n[i - 1])
+                        * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                            + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]));
+
+                q[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {
+                    parabolic
+                } else {
+                    let j = if d > 0.0 { i + 1 } else { i - 1 };
+
+                    q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+                };
+                n[i] += d;
+            }
+        }
+    }
+
+    fn estimate(&self) -> f64 {
+        self.markers[2]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// `count` values from 0 to `max` skewed towards 0, from a deterministic linear
+    /// congruential generator started at `seed`
+    fn skewed_values(mut seed: u64, count: usize, max: f64) -> Vec<f64> {
+        (0..count)
+            .map(|_| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let uniform = (seed >> 11) as f64 / (1u64 << 53) as f64;
+
+                uniform * uniform * max
+            })
+            .collect()
+    }
+
     #[test]
     fn even_test() {
         let quartile =
@@ -161,10 +696,10 @@ mod tests {
 
         assert_eq!(quartile.iqr(), 29.0);
         assert_eq!(quartile.median(), 64.0);
-        assert_eq!(quartile.lower_median(), 52.0);
-        assert_eq!(quartile.upper_median(), 81.0);
-        assert_eq!(quartile.lower_fence(), 8.5);
-        assert_eq!(quartile.upper_fence(), 124.5);
+        assert_eq!(quartile.lower_median(), 50.0);
+        assert_eq!(quartile.upper_median(), 79.0);
+        assert_eq!(quartile.lower_fence(), 6.5);
+        assert_eq!(quartile.upper_fence(), 122.5);
         assert_eq!(quartile.min_before_lower_fence(), 48.0);
         assert_eq!(quartile.max_before_upper_fence(), 88.0);
         assert_eq!(quartile.lower_outliers(), vec![5.0, 6.0]);
@@ -172,4 +707,429 @@ mod tests {
         assert_eq!(quartile.min_value(), 5.0);
         assert_eq!(quartile.max_value(), 88.0);
     }
+
+    #[test]
+    fn three_values_test() {
+        let quartiles = |method| {
+            let quartile =
+                Quartile::with_method(&[3.0, 1.0, 2.0], method, WhiskerMode::Tukey).unwrap();
+
+            (
+                quartile.lower_median(),
+                quartile.median(),
+                quartile.upper_median(),
+            )
+        };
+        let quartile = Quartile::new(&[3.0, 1.0, 2.0]).unwrap();
+
+        assert_eq!(quartiles(QuartileMethod::Exclusive), (1.0, 2.0, 3.0));
+        assert_eq!(quartiles(QuartileMethod::Inclusive), (1.5, 2.0, 2.5));
+        assert_eq!(quartiles(QuartileMethod::Linear), (1.5, 2.0, 2.5));
+        assert_eq!(quartile.iqr(), 2.0);
+        assert_eq!(quartile.min_before_lower_fence(), 1.0);
+        assert_eq!(quartile.max_before_upper_fence(), 3.0);
+        assert!(Quartile::new(&[1.0, 2.0]).is_err());
+    }
+
+    #[test]
+    fn four_values_test() {
+        let quartiles = |method| {
+            let quartile =
+                Quartile::with_method(&[4.0, 2.0, 1.0, 3.0], method, WhiskerMode::Tukey).unwrap();
+
+            (
+                quartile.lower_median(),
+                quartile.median(),
+                quartile.upper_median(),
+            )
+        };
+
+        assert_eq!(quartiles(QuartileMethod::Exclusive), (1.5, 2.5, 3.5));
+        assert_eq!(quartiles(QuartileMethod::Inclusive), (1.5, 2.5, 3.5));
+        assert_eq!(quartiles(QuartileMethod::Linear), (1.75, 2.5, 3.25));
+    }
+
+    #[test]
+    #[cfg(not(feature = "f32"))]
+    fn signed_zero_test() {
+        let quartile = Quartile::new(&[1.0 + f64::EPSILON, 0.0, 2.0, -0.0, 1.0]).unwrap();
+
+        assert!(quartile.min_before_lower_fence().is_sign_negative());
+        assert!(quartile.lower_median().is_sign_positive());
+        assert_eq!(quartile.median(), 1.0);
+        assert_eq!(quartile.upper_median(), 1.5);
+
+        let mut values = [1.0 + f64::EPSILON, 0.0, 1.0, -0.0, 1.0 - f64::EPSILON];
+
+        Quartile::sort(&mut values);
+
+        assert_eq!(
+            values.map(f64::to_bits),
+            [
+                (-0.0f64).to_bits(),
+                0.0f64.to_bits(),
+                (1.0 - f64::EPSILON).to_bits(),
+                1.0f64.to_bits(),
+                (1.0 + f64::EPSILON).to_bits(),
+            ]
+        );
+    }
+
+    #[test]
+    fn outlier_order_test() {
+        // Outliers interleaved with the values 1 to 20 in descending order
+        let mut values: Vec<f64> = (1..=20).rev().map(f64::from).collect();
+
+        for (i, outlier) in [50.0, -40.0, 60.0, -30.0, 60.0, 55.0, -40.0]
+            .into_iter()
+            .enumerate()
+        {
+            values.insert(i * 3, outlier);
+        }
+
+        let is_ascending = |outliers: &[f64]| {
+            outliers
+                .windows(2)
+                .all(|pair| pair[0].total_cmp(&pair[1]).is_le())
+        };
+
+        for whisker_mode in [WhiskerMode::Tukey, WhiskerMode::Percentile(10.0, 90.0)] {
+            let quartile = Quartile::with_whisker_mode(&values, whisker_mode).unwrap();
+
+            assert!(quartile.lower_outliers().len() > 1);
+            assert!(quartile.upper_outliers().len() > 1);
+            assert!(is_ascending(&quartile.lower_outliers()));
+            assert!(is_ascending(&quartile.upper_outliers()));
+        }
+
+        let quartile = Quartile::new(&values).unwrap();
+
+        assert_eq!(quartile.lower_outliers(), vec![-40.0, -40.0, -30.0]);
+        assert_eq!(quartile.upper_outliers(), vec![50.0, 55.0, 60.0, 60.0]);
+    }
+
+    #[test]
+    fn classify_test() {
+        let quartile =
+            Quartile::new(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 100.0]).unwrap();
+
+        assert_eq!(quartile.classify(-10.0), Classification::LowerOutlier);
+        assert_eq!(quartile.classify(-4.5), Classification::Normal);
+        assert_eq!(quartile.classify(5.0), Classification::Normal);
+        assert_eq!(quartile.classify(12.0), Classification::Normal);
+        assert_eq!(quartile.classify(15.5), Classification::Normal);
+        assert_eq!(quartile.classify(16.0), Classification::UpperOutlier);
+
+        // The stored values classify as their outliers do
+        for n in quartile.upper_outliers() {
+            assert_eq!(quartile.classify(n), Classification::UpperOutlier);
+        }
+
+        let values: Vec<f64> = (0..=20).map(|n| n as f64).collect();
+        let quartile =
+            Quartile::with_whisker_mode(&values, WhiskerMode::Percentile(10.0, 90.0)).unwrap();
+
+        assert_eq!(quartile.classify(1.0), Classification::LowerOutlier);
+        assert_eq!(quartile.classify(10.0), Classification::Normal);
+        assert_eq!(quartile.classify(19.0), Classification::UpperOutlier);
+    }
+
+    #[test]
+    fn nan_test() {
+        assert!(Quartile::new(&[1.0, f64::NAN, 3.0]).is_err());
+    }
+
+    #[test]
+    fn upper_outliers_test() {
+        let quartile =
+            Quartile::new(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 100.0, 120.0]).unwrap();
+
+        assert_eq!(quartile.upper_outliers(), vec![100.0, 120.0]);
+        assert_eq!(quartile.max_before_upper_fence(), 9.0);
+        assert_eq!(quartile.max_value(), 120.0);
+    }
+
+    #[test]
+    fn whisker_bounds_test() {
+        let quartile =
+            Quartile::new(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 100.0]).unwrap();
+
+        assert_eq!(quartile.lower_fence(), -4.5);
+        assert_eq!(quartile.upper_fence(), 15.5);
+        assert_eq!(quartile.whisker_bounds(), (1.0, 9.0));
+        assert_eq!(quartile.upper_outliers(), vec![100.0]);
+
+        let values: Vec<f64> = (0..=20).map(|n| n as f64).collect();
+        let quartile =
+            Quartile::with_whisker_mode(&values, WhiskerMode::Percentile(10.0, 90.0)).unwrap();
+
+        assert_eq!(quartile.whisker_bounds(), (2.0, 18.0));
+
+        // Interpolated percentiles are pulled in to the nearest values inside them
+        let values = [1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0, 512.0];
+
+        for (lower, upper) in [(5.0, 95.0), (10.0, 90.0), (12.5, 87.5), (20.0, 80.0)] {
+            let quartile =
+                Quartile::with_whisker_mode(&values, WhiskerMode::Percentile(lower, upper))
+                    .unwrap();
+            let (low, high) = quartile.whisker_bounds();
+
+            assert!(values.contains(&low), "{} at {}", low, lower);
+            assert!(values.contains(&high), "{} at {}", high, upper);
+            assert!(low >= quartile.lower_fence());
+            assert!(high <= quartile.upper_fence());
+        }
+
+        let quartile =
+            Quartile::with_whisker_mode(&values, WhiskerMode::Percentile(10.0, 90.0)).unwrap();
+
+        assert_eq!(quartile.whisker_bounds(), (2.0, 256.0));
+    }
+
+    #[test]
+    fn percentile_whisker_test() {
+        let values: Vec<f64> = (0..=20).map(|n| n as f64).collect();
+        let quartile =
+            Quartile::with_whisker_mode(&values, WhiskerMode::Percentile(10.0, 90.0)).unwrap();
+
+        assert_eq!(quartile.percentile(25.0), Some(5.0));
+        assert_eq!(quartile.percentile(12.5), Some(2.5));
+        assert_eq!(quartile.min_before_lower_fence(), 2.0);
+        assert_eq!(quartile.max_before_upper_fence(), 18.0);
+        assert_eq!(quartile.lower_outliers(), vec![0.0, 1.0]);
+        assert_eq!(quartile.upper_outliers(), vec![19.0, 20.0]);
+        assert!(Quartile::with_whisker_mode(&values, WhiskerMode::Percentile(90.0, 10.0)).is_err());
+    }
+
+    #[test]
+    fn symmetric_quartile_test() {
+        let values: Vec<f64> = (1..=12).map(|n| n as f64).collect();
+        let reversed: Vec<f64> = values.iter().map(|n| 13.0 - n).collect();
+
+        for method in [
+            QuartileMethod::Exclusive,
+            QuartileMethod::Inclusive,
+            QuartileMethod::Linear,
+        ] {
+            let quartile = Quartile::with_method(&values, method, WhiskerMode::Tukey).unwrap();
+            let negated: Vec<f64> = values.iter().map(|n| -n).collect();
+            let mirrored = Quartile::with_method(&negated, method, WhiskerMode::Tukey).unwrap();
+
+            // Quartiles are equally far from the median and mirror when the data is negated
+            assert_eq!(
+                quartile.median() - quartile.lower_median(),
+                quartile.upper_median() - quartile.median()
+            );
+            assert_eq!(quartile.lower_median(), -mirrored.upper_median());
+            assert_eq!(quartile.upper_median(), -mirrored.lower_median());
+            assert_eq!(
+                quartile,
+                Quartile::with_method(&reversed, method, WhiskerMode::Tukey).unwrap()
+            );
+        }
+
+        let quartile = Quartile::new(&values).unwrap();
+
+        assert_eq!(quartile.lower_median(), 3.5);
+        assert_eq!(quartile.upper_median(), 9.5);
+
+        let odd: Vec<f64> = (1..=13).map(|n| n as f64).collect();
+        let inclusive =
+            Quartile::with_method(&odd, QuartileMethod::Inclusive, WhiskerMode::Tukey).unwrap();
+        let linear =
+            Quartile::with_method(&odd, QuartileMethod::Linear, WhiskerMode::Tukey).unwrap();
+
+        assert_eq!(Quartile::new(&odd).unwrap().lower_median(), 3.5);
+        assert_eq!(inclusive.lower_median(), 4.0);
+        assert_eq!(inclusive.upper_median(), 10.0);
+        assert_eq!(linear.lower_median(), 4.0);
+        assert_eq!(linear.upper_median(), 10.0);
+    }
+
+    #[test]
+    fn variance_test() {
+        let quartile = Quartile::new(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+
+        assert_eq!(quartile.mean(), Some(5.0));
+        assert_eq!(quartile.variance(VarianceKind::Population), Some(4.0));
+        assert_eq!(quartile.std_dev(VarianceKind::Population), Some(2.0));
+        assert_eq!(quartile.variance(VarianceKind::Sample), Some(32.0 / 7.0));
+        assert_eq!(
+            quartile.std_dev(VarianceKind::Sample),
+            Some((32.0_f64 / 7.0).sqrt())
+        );
+
+        // A summary has no values to take statistics of
+        let summary = Quartile::from_summary(2.0, 4.0, 4.5, 6.0, 9.0).unwrap();
+
+        assert!(!summary.has_values());
+        assert_eq!(summary.count(), 0);
+        assert_eq!(summary.values(), None);
+        assert_eq!(summary.mean(), None);
+        assert_eq!(summary.variance(VarianceKind::Sample), None);
+        assert_eq!(summary.std_dev(VarianceKind::Population), None);
+        assert_eq!(summary.percentile(50.0), None);
+        assert_eq!(summary.median(), 4.5);
+    }
+
+    #[test]
+    fn approx_quartile_test() {
+        let values = skewed_values(42, 100_000, 1000.0);
+        let exact = Quartile::new(&values).unwrap();
+        let mut approx = ApproxQuartile::new();
+
+        approx.extend(values.iter().cloned());
+
+        let estimate = approx.to_quartile().unwrap();
+
+        assert_eq!(estimate.sample_size(), Some(values.len()));
+
+        let tolerance = 0.01 * (exact.max_value() - exact.min_value());
+
+        assert_eq!(approx.count(), values.len());
+        assert!((estimate.lower_median() - exact.lower_median()).abs() < tolerance);
+        assert!((estimate.median() - exact.median()).abs() < tolerance);
+        assert!((estimate.upper_median() - exact.upper_median()).abs() < tolerance);
+        assert_eq!(estimate.min_value(), exact.min_value());
+        assert_eq!(estimate.max_value(), exact.max_value());
+
+        let mut small = ApproxQuartile::new();
+
+        small.extend([3.0, 1.0, 2.0, 5.0]);
+
+        assert_eq!(
+            small.to_quartile().unwrap(),
+            Quartile::new(&[1.0, 2.0, 3.0, 5.0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn histogram_test() {
+        let values = skewed_values(7, 10_000, 100.0);
+        let exact =
+            Quartile::with_method(&values, QuartileMethod::Linear, WhiskerMode::Tukey).unwrap();
+        // Bins one unit wide, centered on each half unit
+        let mut counts = [0u64; 100];
+
+        for value in &values {
+            counts[(*value as usize).min(99)] += 1;
+        }
+
+        let bins: Vec<(f64, u64)> = counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| (i as f64 + 0.5, *count))
+            .collect();
+        let estimate = Quartile::from_histogram(&bins).unwrap();
+
+        assert!(!estimate.has_values());
+        assert_eq!(estimate.sample_size(), Some(values.len()));
+        assert!((estimate.lower_median() - exact.lower_median()).abs() <= 0.5);
+        assert!((estimate.median() - exact.median()).abs() <= 0.5);
+        assert!((estimate.upper_median() - exact.upper_median()).abs() <= 0.5);
+        assert!((estimate.min_value() - exact.min_value()).abs() <= 0.5);
+        assert!((estimate.max_value() - exact.max_value()).abs() <= 0.5);
+
+        // Unbinned values give exactly the linear quartiles
+        assert_eq!(
+            Quartile::from_histogram(&[(5.0, 1), (1.0, 2), (3.0, 0), (2.0, 1)])
+                .unwrap()
+                .median(),
+            Quartile::with_method(
+                &[1.0, 1.0, 2.0, 5.0],
+                QuartileMethod::Linear,
+                WhiskerMode::Tukey
+            )
+            .unwrap()
+            .median()
+        );
+        assert!(Quartile::from_histogram(&[(1.0, 2), (2.0, 0)]).is_err());
+        assert!(Quartile::from_histogram(&[(f64::NAN, 3)]).is_err());
+    }
+
+    #[test]
+    fn mostly_outliers_test() {
+        let values: Vec<f64> = (0..=20).map(|n| n as f64).collect();
+        let quartile =
+            Quartile::with_whisker_mode(&values, WhiskerMode::Percentile(49.0, 51.0)).unwrap();
+
+        assert_eq!(quartile.lower_outliers().len(), 10);
+        assert_eq!(quartile.upper_outliers().len(), 10);
+        assert_eq!(quartile.min_before_lower_fence(), quartile.lower_median());
+        assert_eq!(quartile.max_before_upper_fence(), quartile.upper_median());
+
+        let quartile = Quartile::new(&[
+            -100.0, -50.0, -20.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 20.0, 50.0, 100.0,
+        ])
+        .unwrap();
+
+        assert_eq!(quartile.iqr(), 0.0);
+        assert_eq!(quartile.lower_outliers(), vec![-100.0, -50.0, -20.0]);
+        assert_eq!(quartile.upper_outliers(), vec![20.0, 50.0, 100.0]);
+        assert_eq!(quartile.min_before_lower_fence(), 0.0);
+        assert_eq!(quartile.max_before_upper_fence(), 0.0);
+    }
+}
+
+#[cfg(all(test, feature = "f32"))]
+mod f32_tests {
+    use super::*;
+
+    #[test]
+    fn f32_quartile_test() {
+        let quartile =
+            Quartile::new(&[48.0, 52.0, 57.0, 64.0, 72.0, 76.0, 77.0, 81.0, 85.0, 88.0]).unwrap();
+
+        assert_eq!(std::mem::size_of::<Float>(), 4);
+        assert_eq!(quartile.median(), 74.0);
+        assert_eq!(quartile.iqr(), 24.0);
+        assert_eq!(quartile.upper_fence(), 117.0);
+        assert_eq!(quartile.percentile(50.0), Some(74.0));
+
+        // The public API stays in f64 with the feature enabled
+        let mut values: [f64; 3] = [1.0, -0.0, 0.0];
+
+        Quartile::sort(&mut values);
+
+        assert_eq!(
+            values.map(f64::to_bits),
+            [(-0.0f64).to_bits(), 0.0f64.to_bits(), 1.0f64.to_bits()]
+        );
+    }
+
+    #[test]
+    fn f32_signed_zero_test() {
+        let quartile =
+            Quartile::new(&[1.0 + f64::from(f32::EPSILON), 0.0, 2.0, -0.0, 1.0]).unwrap();
+
+        assert!(quartile.min_before_lower_fence().is_sign_negative());
+        assert!(quartile.lower_median().is_sign_positive());
+        assert_eq!(quartile.median(), 1.0);
+
+        // Stored as f32 but sorted and returned the same way as f64
+        let quartile = Quartile::new(&[
+            1.0 + f64::from(f32::EPSILON),
+            0.0,
+            1.0,
+            -0.0,
+            1.0 - f64::from(f32::EPSILON),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            quartile
+                .values()
+                .unwrap()
+                .iter()
+                .map(|n| n.to_bits())
+                .collect::<Vec<_>>(),
+            [
+                (-0.0f64).to_bits(),
+                0.0f64.to_bits(),
+                (1.0 - f64::from(f32::EPSILON)).to_bits(),
+                1.0f64.to_bits(),
+                (1.0 + f64::from(f32::EPSILON)).to_bits(),
+            ]
+        );
+    }
 }
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// Statistics of a chart's values as a whole and of each of its categories
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChartSummary {
+    pub title: String,
+    pub units: String,
+    /// Every value of every category, excluding any total, or `None` when a category was
+    /// built from a summary and has no values
+    pub global: Option<GlobalSummary>,
+    pub categories: Vec<CategorySummary>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GlobalSummary {
+    pub count: usize,
+    pub min: f64,
+    pub median: f64,
+    pub max: f64,
+}
+
+/// The five-number summary and outliers of one category
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CategorySummary {
+    pub key: String,
+    /// The number of values, if known
+    pub count: Option<usize>,
+    pub min: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub max: f64,
+    /// The mean of the values, or `None` for a category built from a summary
+    pub mean: Option<f64>,
+    pub lower_outliers: Vec<f64>,
+    pub upper_outliers: Vec<f64>,
+}
@@ -0,0 +1,156 @@
+use std::error::Error;
+use svg::{
+    node::element::tag::Type,
+    parser::{Event, Parser},
+    Node,
+};
+
+/// Insert `chart` into the element of an SVG `template` whose `id` attribute is
+/// `placeholder_id`.  The rest of the template, including its dimensions, is left untouched.
+pub fn inject<T: Node>(
+    template: &str,
+    placeholder_id: &str,
+    chart: &T,
+) -> Result<String, Box<dyn Error>> {
+    let mut placeholder: Option<(String, Type)> = None;
+
+    for event in Parser::new(template) {
+        match event {
+            Event::Error(err) => {
+                return Err(From::from(format!("Unable to parse SVG template: {}", err)))
+            }
+            Event::Tag(name, tag_type, attributes)
+                if tag_type != Type::End
+                    && attributes.get("id").map(|v| v.to_string()).as_deref()
+                        == Some(placeholder_id) =>
+            {
+                placeholder = Some((name.to_owned(), tag_type));
+                break;
+            }
+            _ => (),
+        }
+    }
+
+    let (name, tag_type) = match placeholder {
+        Some(p) => p,
+        None => {
+            return Err(From::from(format!(
+                "SVG template has no element with id '{}'",
+                placeholder_id
+            )))
+        }
+    };
+    let (start, end) = find_tag(template, &name, placeholder_id).ok_or_else(|| {
+        format!(
+            "Unable to locate element with id '{}' in SVG template",
+            placeholder_id
+        )
+    })?;
+    let mut content = String::with_capacity(template.len());
+
+    if tag_type == Type::Empty {
+        let open_tag = template[start..end - 1].trim_end_matches('/').trim_end();
+
+        content.push_str(&template[..start]);
+        content.push_str(&format!("{}>\n{}\n</{}>", open_tag, chart, name));
+    } else {
+        content.push_str(&template[..end]);
+        content.push_str(&format!("\n{}", chart));
+    }
+
+    content.push_str(&template[end..]);
+
+    Ok(content)
+}
+
+/// Find the byte range of the opening tag `name` with the given `id`
+fn find_tag(template: &str, name: &str, id: &str) -> Option<(usize, usize)> {
+    let open = format!("<{}", name);
+    let mut offset = 0;
+
+    while let Some(found) = template[offset..].find(&open) {
+        let start = offset + found;
+        let end = start + template[start..].find('>')? + 1;
+        let tag = &template[start..end];
+        let is_whole_name = tag[open.len()..].starts_with(|c: char| c.is_whitespace());
+
+        if is_whole_name && attribute_value(tag, "id") == Some(id) {
+            return Some((start, end));
+        }
+
+        offset = end;
+    }
+
+    None
+}
+
+/// The quoted value of the attribute `name` of an opening tag, which must be preceded by
+/// whitespace so that, for example, `data-id` is not taken for `id`
+fn attribute_value<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut offset = 0;
+
+    while let Some(found) = tag[offset..].find(name) {
+        let start = offset + found;
+        let rest = tag[start + name.len()..].trim_start();
+
+        offset = start + name.len();
+
+        if !tag[..start].ends_with(|c: char| c.is_whitespace()) {
+            continue;
+        }
+
+        if let Some(rest) = rest.strip_prefix('=').map(str::trim_start) {
+            let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let value = &rest[1..];
+
+            return value.find(quote).map(|end| &value[..end]);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use svg::node::element::Group;
+
+    const TEMPLATE: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="600">
+<rect class="banner" width="800" height="50"/>
+<g id="chart" transform="translate(20,60)"/>
+<text x="10" y="590">Company Confidential</text>
+</svg>"#;
+
+    #[test]
+    fn inject_empty_placeholder_test() {
+        let chart = Group::new().set("class", "chart");
+        let content = inject(TEMPLATE, "chart", &chart).unwrap();
+        let placeholder = content
+            .find(r#"<g id="chart" transform="translate(20,60)">"#)
+            .unwrap();
+        let group = content.find(r#"<g class="chart"/>"#).unwrap();
+        let close = content[group..].find("</g>").unwrap() + group;
+
+        assert!(content
+            .starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="600">"#));
+        assert!(placeholder < group);
+        assert!(close < content.find("Company Confidential").unwrap());
+    }
+
+    #[test]
+    fn find_tag_test() {
+        let template =
+            r#"<svg><g data-id="chart"/><g id="charts"/><gx id="chart"/><g  id = 'chart' /></svg>"#;
+        let (start, end) = find_tag(template, "g", "chart").unwrap();
+
+        assert_eq!(&template[start..end], "<g  id = 'chart' />");
+        assert_eq!(find_tag(template, "g", "chart-2"), None);
+    }
+
+    #[test]
+    fn inject_missing_placeholder_test() {
+        let chart = Group::new();
+
+        assert!(inject(TEMPLATE, "missing", &chart).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::{error::Error, path::Path, sync::mpsc};
+
+/// Call `on_change` each time the file at `path` is created or modified, for as long as
+/// `on_change` returns `true`.  The parent directory is watched so that editors which
+/// replace the file on save are still noticed.
+pub fn watch_file<F>(path: &Path, mut on_change: F) -> Result<(), Box<dyn Error>>
+where
+    F: FnMut() -> Result<bool, Box<dyn Error>>,
+{
+    let path = path.canonicalize()?;
+    let parent = path
+        .parent()
+        .ok_or_else(|| format!("Unable to watch '{}'", path.to_string_lossy()))?;
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+
+    watcher.watch(parent, RecursiveMode::NonRecursive)?;
+
+    for result in receiver {
+        let event = result?;
+
+        if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+            && event.paths.contains(&path)
+            && !on_change()?
+        {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common::TempDir;
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn watch_file_test() {
+        let dir = TempDir::new("watch");
+        let path = dir.join("input.json5");
+
+        std::fs::write(&path, "{}").unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let watch_path = path.clone();
+
+        thread::spawn(move || {
+            let mut renders = 0;
+            let result = watch_file(&watch_path, || {
+                renders += 1;
+                Ok(false)
+            });
+
+            sender
+                .send(result.map(|_| renders).map_err(|err| err.to_string()))
+                .unwrap();
+        });
+
+        // Keep changing the file until the watcher, which may not be ready at first, notices
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let renders = loop {
+            std::fs::write(&path, "{ changed: true }").unwrap();
+
+            match receiver.recv_timeout(Duration::from_millis(250)) {
+                Ok(result) => break result.unwrap(),
+                Err(_) if Instant::now() < deadline => (),
+                Err(_) => panic!("No change to '{}' was noticed", path.to_string_lossy()),
+            }
+        };
+
+        assert_eq!(renders, 1);
+    }
+}
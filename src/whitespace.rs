@@ -0,0 +1,168 @@
+/// Remove the whitespace between the tags of an SVG document and collapse the whitespace
+/// within its text
+pub fn minify(svg: &str) -> String {
+    let mut content = String::with_capacity(svg.len());
+
+    for token in tokens(svg) {
+        match token {
+            Token::Tag(tag) => content.push_str(tag),
+            Token::Text(text) => {
+                content.push_str(&text.split_whitespace().collect::<Vec<_>>().join(" "))
+            }
+        }
+    }
+
+    content
+}
+
+/// Put each tag and line of text of an SVG document on its own line, indented by depth
+pub fn pretty(svg: &str) -> String {
+    let mut content = String::with_capacity(svg.len() * 2);
+    let mut depth: usize = 0;
+    let mut push_line = |depth: usize, line: &str| {
+        content.push_str(&"  ".repeat(depth));
+        content.push_str(line);
+        content.push('\n');
+    };
+
+    for token in tokens(svg) {
+        match token {
+            Token::Tag(tag) if tag.starts_with("</") => {
+                depth = depth.saturating_sub(1);
+                push_line(depth, tag);
+            }
+            Token::Tag(tag)
+                if tag.ends_with("/>") || tag.starts_with("<?") || tag.starts_with("<!") =>
+            {
+                push_line(depth, tag)
+            }
+            Token::Tag(tag) => {
+                push_line(depth, tag);
+                depth += 1;
+            }
+            Token::Text(text) => {
+                for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+                    push_line(depth, line);
+                }
+            }
+        }
+    }
+
+    content
+}
+
+enum Token<'a> {
+    Tag(&'a str),
+    Text(&'a str),
+}
+
+/// Elements whose content is text that can hold `<` and `>`, such as CSS child selectors
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["style", "script"];
+
+/// Split a document into tags and the text between them.  Comments and CDATA sections are
+/// single tags, and the content of a `<style>` or `<script>` element is a single text.
+fn tokens(svg: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = svg;
+
+    while let Some(start) = rest.find('<') {
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+
+        let end = start + tag_len(&rest[start..]);
+        let tag = &rest[start..end];
+
+        tokens.push(Token::Tag(tag));
+        rest = &rest[end..];
+
+        if let Some(name) = raw_text_element(tag) {
+            let len = rest.find(&format!("</{}", name)).unwrap_or(rest.len());
+
+            if len > 0 {
+                tokens.push(Token::Text(&rest[..len]));
+            }
+
+            rest = &rest[len..];
+        }
+    }
+
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest));
+    }
+
+    tokens
+}
+
+/// The length of the tag at the start of `s`, ending at the first `>` outside of quoted
+/// attribute values, or the whole of `s` if the tag is not closed
+fn tag_len(s: &str) -> usize {
+    for (open, close) in [("<!--", "-->"), ("<![CDATA[", "]]>")] {
+        if let Some(content) = s.strip_prefix(open) {
+            return content
+                .find(close)
+                .map_or(s.len(), |end| open.len() + end + close.len());
+        }
+    }
+
+    let mut quote = None;
+
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return i + 1,
+            _ => (),
+        }
+    }
+
+    s.len()
+}
+
+/// The name of the raw text element opened by `tag`, if it is one
+fn raw_text_element(tag: &str) -> Option<&'static str> {
+    if tag.ends_with("/>") {
+        return None;
+    }
+
+    RAW_TEXT_ELEMENTS.into_iter().find(|name| {
+        tag[1..]
+            .strip_prefix(name)
+            .is_some_and(|after| after.starts_with(|c: char| c == '>' || c.is_whitespace()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SVG: &str = "<svg width=\"10\">\n<style>\n.a{fill:none;}\n.b{fill:red;}\n</style>\n<g>\n<text x=\"1\">\nHello  world\n</text>\n<line/>\n</g>\n</svg>";
+
+    #[test]
+    fn minify_test() {
+        assert_eq!(
+            minify(SVG),
+            "<svg width=\"10\"><style>.a{fill:none;} .b{fill:red;}</style><g><text x=\"1\">Hello world</text><line/></g></svg>"
+        );
+    }
+
+    #[test]
+    fn angle_brackets_test() {
+        let svg = "<svg>\n<style>\ng > text{fill:red;}\n</style>\n<!-- a < b -->\n<g aria-label=\"a > b\" data-c='<c>'>\n<text>\n<![CDATA[1 < 2]]>\n</text>\n</g>\n</svg>";
+        let minified = "<svg><style>g > text{fill:red;}</style><!-- a < b --><g aria-label=\"a > b\" data-c='<c>'><text><![CDATA[1 < 2]]></text></g></svg>";
+
+        assert_eq!(minify(svg), minified);
+        assert_eq!(
+            pretty(minified),
+            "<svg>\n  <style>\n    g > text{fill:red;}\n  </style>\n  <!-- a < b -->\n  <g aria-label=\"a > b\" data-c='<c>'>\n    <text>\n      <![CDATA[1 < 2]]>\n    </text>\n  </g>\n</svg>\n"
+        );
+    }
+
+    #[test]
+    fn pretty_test() {
+        assert_eq!(
+            pretty(&minify(SVG)),
+            "<svg width=\"10\">\n  <style>\n    .a{fill:none;} .b{fill:red;}\n  </style>\n  <g>\n    <text x=\"1\">\n      Hello world\n    </text>\n    <line/>\n  </g>\n</svg>\n"
+        );
+    }
+}
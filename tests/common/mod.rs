@@ -0,0 +1,37 @@
+//! Helpers shared by the integration tests and the unit tests in `src/lib.rs`
+use std::{
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+/// A scratch directory for the files of one test, removed along with them when dropped
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> TempDir {
+        let dir =
+            std::env::temp_dir().join(format!("box_plot_chart_{}_{}", name, std::process::id()));
+
+        std::fs::create_dir_all(&dir).unwrap();
+        TempDir(dir)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// The example chart data
+pub fn example() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("example/example.json5")
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::{example, TempDir};
+use std::{path::PathBuf, process::Command};
+
+fn run(args: &[&str]) -> i32 {
+    Command::new(env!("CARGO_BIN_EXE_box-plot-chart"))
+        .args(args)
+        .output()
+        .unwrap()
+        .status
+        .code()
+        .unwrap()
+}
+
+fn write_input(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+    let path = dir.join(name);
+
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+fn example_arg() -> String {
+    example().to_string_lossy().to_string()
+}
+
+#[test]
+fn success_exit_code() {
+    let dir = TempDir::new("exit_success");
+    let output = dir.join("success.svg");
+
+    assert_eq!(run(&[&example_arg(), output.to_str().unwrap()]), 0);
+    assert_eq!(run(&["--help"]), 0);
+}
+
+#[test]
+fn usage_exit_code() {
+    assert_eq!(run(&["--no-such-flag"]), 2);
+    assert_eq!(run(&[&example_arg(), "--scale", "0"]), 2);
+    assert_eq!(run(&[&example_arg(), "--scale=-1"]), 2);
+
+    // Outputs describing a single chart cannot take several
+    for flags in [
+        &["--title-only"][..],
+        &["--output-format", "text"],
+        &["--layout-json", "layout.json"],
+        &["--summary-json", "summary.json"],
+        &["--per-page", "2"],
+    ] {
+        let mut args = vec![example_arg(), "--grid-input".to_owned(), example_arg()];
+
+        args.extend(flags.iter().map(|flag| flag.to_string()));
+
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+        assert_eq!(run(&args), 2, "{:?}", flags);
+    }
+}
+
+#[test]
+fn invalid_option_exit_code() {
+    let dir = TempDir::new("exit_invalid_option");
+    let color_map = write_input(&dir, "invalid_colors.json5", "{ a: ");
+
+    for flags in [
+        &["--sig-figs", "0"][..],
+        &["--y-intervals", "0"],
+        &["--max-ticks", "0"],
+        &["--whisker-gap=-1"],
+        &["--locale", "xx"],
+        &["--color-map", "/no/such/colors.json5"],
+        &["--color-map", color_map.to_str().unwrap()],
+        &["--template-svg", "/no/such/template.svg"],
+    ] {
+        let mut args = vec![example_arg()];
+
+        args.extend(flags.iter().map(|flag| flag.to_string()));
+
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+        assert_eq!(run(&args), 2, "{:?}", flags);
+    }
+}
+
+#[test]
+fn usage_error_reported_once() {
+    let output = Command::new(env!("CARGO_BIN_EXE_box-plot-chart"))
+        .arg("--no-such-flag")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.stdout.is_empty());
+    assert_eq!(stderr.matches("error:").count(), 1);
+    assert!(stderr.contains("--no-such-flag"));
+    assert!(stderr.contains("USAGE:"));
+}
+
+#[test]
+fn input_not_found_exit_code() {
+    assert_eq!(run(&["/no/such/input.json5"]), 3);
+}
+
+#[test]
+fn parse_exit_code() {
+    let dir = TempDir::new("exit_parse");
+    let input = write_input(&dir, "invalid.json5", "{ title: ");
+
+    assert_eq!(run(&[input.to_str().unwrap()]), 4);
+}
+
+#[test]
+fn render_exit_code() {
+    let dir = TempDir::new("exit_render");
+    let input = write_input(
+        &dir,
+        "too_few.json5",
+        r#"{ title: "T", units: "ms", data: [{ key: "a", values: [1.0] }] }"#,
+    );
+
+    assert_eq!(run(&[input.to_str().unwrap()]), 5);
+}
+
+#[test]
+fn write_exit_code() {
+    assert_eq!(run(&[&example_arg(), "/no/such/directory/output.svg"]), 6);
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::{example, TempDir};
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+#[test]
+fn stdin_to_output_flag() {
+    let dir = TempDir::new("paths_stdin");
+    let output = dir.join("out.svg");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_box-plot-chart"))
+        .args(["--format", "json5", "--output"])
+        .arg(&output)
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(&std::fs::read(example()).unwrap())
+        .unwrap();
+
+    assert!(child.wait().unwrap().success());
+    assert!(std::fs::read_to_string(&output)
+        .unwrap()
+        .starts_with("<svg"));
+}
+
+#[test]
+fn input_flag_with_positional_output() {
+    let dir = TempDir::new("paths_input");
+    let output = dir.join("out.svg");
+    let status = Command::new(env!("CARGO_BIN_EXE_box-plot-chart"))
+        .arg("--input")
+        .arg(example())
+        .arg(&output)
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+    assert!(output.exists());
+
+    let status = Command::new(env!("CARGO_BIN_EXE_box-plot-chart"))
+        .arg("--input")
+        .arg(example())
+        .arg(example())
+        .arg(&output)
+        .status()
+        .unwrap();
+
+    assert_eq!(status.code(), Some(2));
+}
@@ -0,0 +1,60 @@
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+const CSV: &str = "north, 1, 2, 3\nsouth,4,5,6,7\n";
+
+fn run_with_stdin(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_box-plot-chart"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn csv_stdin_with_format_flag() {
+    let output = run_with_stdin(&["--format", "csv"], CSV);
+    let svg = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains("north"));
+    assert!(svg.contains("south"));
+
+    // Read as JSON5, the same input is a parse error
+    assert_eq!(
+        run_with_stdin(&["--format", "json5"], CSV).status.code(),
+        Some(4)
+    );
+}
+
+#[test]
+fn csv_stdin_sniffed() {
+    let sniffed = run_with_stdin(&[], CSV);
+
+    assert!(sniffed.status.success());
+    assert_eq!(
+        sniffed.stdout,
+        run_with_stdin(&["--format", "csv"], CSV).stdout
+    );
+
+    let json5 = r#"{ title: "Sniffed", units: "ms", data: { north: [1, 2, 3] } }"#;
+    let sniffed = run_with_stdin(&[], json5);
+
+    assert!(sniffed.status.success());
+    assert!(String::from_utf8(sniffed.stdout)
+        .unwrap()
+        .contains("Sniffed"));
+}